@@ -0,0 +1,248 @@
+use crate::rules::ProductPrices;
+use crate::economy::{
+    Inventory, InventoryError, LOAN_INTEREST_RATE, SAVINGS_INTEREST_RATE, LOAN_TERM, LOAN_GRACE_PERIOD, LOAN_LATE_FEE,
+    CREDIT_LIMIT_FACTOR, MIN_CREDIT_LIMIT, BOUNTY_HUNTER_STRIKES,
+};
+
+pub struct Loan {
+    pub balance: f32,
+    pub due_at: f32,           // Game time the loan must be repaid by
+    pub missed_payments: u32,
+}
+
+// What the bank did to collect on an overdue loan
+pub enum BankPenalty {
+    SavingsSeized(i32),
+    CargoSeized(i32),
+    LateFee(i32),
+    BountyHunters,
+}
+
+impl BankPenalty {
+    pub fn describe(&self) -> String {
+        match self {
+            BankPenalty::SavingsSeized(amount) => format!("Loan overdue - bank seized ${} of savings", amount),
+            BankPenalty::CargoSeized(amount) => format!("Loan overdue - bank seized ${} of cargo", amount),
+            BankPenalty::LateFee(amount) => format!("Loan overdue - ${} late fee added", amount),
+            BankPenalty::BountyHunters => "Repeated defaults - bounty hunters are after you!".to_string(),
+        }
+    }
+}
+
+// Why the bank turned a request down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BankError {
+    OverCreditLimit { requested: i32, available: i32 },
+    NoDebt,
+    NoSavings,
+    Inventory(InventoryError),
+}
+
+impl BankError {
+    pub fn describe(&self) -> String {
+        match self {
+            BankError::OverCreditLimit { requested, available } => {
+                format!("Loan refused - asked for ${}, credit left ${}", requested, available)
+            }
+            BankError::NoDebt => "Nothing to repay".to_string(),
+            BankError::NoSavings => "No savings to withdraw".to_string(),
+            BankError::Inventory(err) => err.describe(),
+        }
+    }
+}
+
+impl From<InventoryError> for BankError {
+    fn from(err: InventoryError) -> Self {
+        BankError::Inventory(err)
+    }
+}
+
+// The player's account with the planetary banks. Balances are kept as f32 so
+// interest can accrue smoothly every frame; they are rounded when shown or paid out.
+pub struct Bank {
+    pub savings: f32,
+    pub loans: Vec<Loan>,
+    prices: ProductPrices, // What cargo is worth as collateral
+}
+
+impl Bank {
+    pub fn new(prices: ProductPrices) -> Self {
+        Self {
+            savings: 0.0,
+            loans: Vec::new(),
+            prices,
+        }
+    }
+
+    pub fn total_debt(&self) -> i32 {
+        self.loans.iter().map(|loan| loan.balance).sum::<f32>().ceil() as i32
+    }
+
+    pub fn next_due(&self) -> Option<f32> {
+        self.loans.iter().map(|loan| loan.due_at).min_by(f32::total_cmp)
+    }
+
+    // Money, savings and cargo at local sell prices, minus everything owed
+    pub fn net_worth(&self, inventory: &Inventory) -> i32 {
+        let cargo_value: i32 = inventory.cargo.iter().map(|(&product, amount)| self.prices.sell_price(product) * amount).sum();
+        inventory.money + self.savings as i32 + cargo_value - self.total_debt()
+    }
+
+    pub fn credit_limit(&self, inventory: &Inventory) -> i32 {
+        ((self.net_worth(inventory) as f32 * CREDIT_LIMIT_FACTOR) as i32).max(MIN_CREDIT_LIMIT)
+    }
+
+    pub fn available_credit(&self, inventory: &Inventory) -> i32 {
+        (self.credit_limit(inventory) - self.total_debt()).max(0)
+    }
+
+    pub fn is_hunting(&self) -> bool {
+        self.loans.iter().any(|loan| loan.missed_payments >= BOUNTY_HUNTER_STRIKES)
+    }
+
+    pub fn borrow(&mut self, inventory: &mut Inventory, amount: i32, now: f32) -> Result<(), BankError> {
+        let available = self.available_credit(inventory);
+        if amount > available {
+            return Err(BankError::OverCreditLimit { requested: amount, available });
+        }
+        self.loans.push(Loan {
+            balance: amount as f32,
+            due_at: now + LOAN_TERM,
+            missed_payments: 0,
+        });
+        inventory.money += amount;
+        Ok(())
+    }
+
+    // Pays off the loans due soonest first, returns how much was paid. Only
+    // whole dollars change hands, so a fractional remainder is rounded in the player's favour.
+    pub fn repay(&mut self, inventory: &mut Inventory, amount: i32) -> Result<i32, BankError> {
+        if self.loans.is_empty() {
+            return Err(BankError::NoDebt);
+        }
+        if inventory.money <= 0 {
+            return Err(InventoryError::InsufficientFunds { cost: amount.min(self.total_debt()), available: inventory.money }.into());
+        }
+        let budget = amount.min(inventory.money);
+        let mut remaining = budget as f32;
+        self.loans.sort_by(|a, b| a.due_at.total_cmp(&b.due_at));
+        for loan in &mut self.loans {
+            let payment = remaining.min(loan.balance);
+            loan.balance -= payment;
+            remaining -= payment;
+        }
+        // Anything under a cent left after rounding counts as paid
+        self.loans.retain(|loan| loan.balance >= 0.01);
+
+        let paid = budget - remaining.ceil() as i32;
+        inventory.money -= paid;
+        Ok(paid)
+    }
+
+    pub fn deposit(&mut self, inventory: &mut Inventory, amount: i32) -> Result<(), BankError> {
+        inventory.spend(amount)?;
+        self.savings += amount as f32;
+        Ok(())
+    }
+
+    pub fn withdraw(&mut self, inventory: &mut Inventory, amount: i32) -> Result<(), BankError> {
+        let amount = amount.min(self.savings.floor() as i32);
+        if amount <= 0 {
+            return Err(BankError::NoSavings);
+        }
+        self.savings -= amount as f32;
+        inventory.money += amount;
+        Ok(())
+    }
+
+    pub fn update(&mut self, dt: f32, now: f32, inventory: &mut Inventory) -> Vec<BankPenalty> {
+        // Continuous interest, rates are per minute
+        let minutes = dt / 60.0;
+        self.savings *= 1.0 + SAVINGS_INTEREST_RATE * minutes;
+        for loan in &mut self.loans {
+            loan.balance *= 1.0 + LOAN_INTEREST_RATE * minutes;
+        }
+
+        let mut penalties = Vec::new();
+        for i in 0..self.loans.len() {
+            if self.loans[i].due_at > now {
+                continue;
+            }
+
+            // Collect what we can: savings first, then the cargo hold
+            let seized_savings = self.savings.min(self.loans[i].balance);
+            if seized_savings >= 1.0 {
+                self.savings -= seized_savings;
+                self.loans[i].balance -= seized_savings;
+                penalties.push(BankPenalty::SavingsSeized(seized_savings as i32));
+            }
+            if self.loans[i].balance >= 0.01 && !inventory.cargo.is_empty() {
+                let prices = self.prices;
+                let cargo_value: i32 = inventory.cargo.drain().map(|(product, amount)| prices.sell_price(product) * amount).sum();
+                self.loans[i].balance -= cargo_value as f32;
+                penalties.push(BankPenalty::CargoSeized(cargo_value));
+            }
+
+            // Still owing: extend the deadline, but it costs and counts against you
+            let loan = &mut self.loans[i];
+            if loan.balance >= 0.01 {
+                loan.balance += LOAN_LATE_FEE as f32;
+                loan.due_at = now + LOAN_GRACE_PERIOD;
+                loan.missed_payments += 1;
+                penalties.push(BankPenalty::LateFee(LOAN_LATE_FEE));
+                if loan.missed_payments == BOUNTY_HUNTER_STRIKES {
+                    penalties.push(BankPenalty::BountyHunters);
+                }
+            }
+        }
+
+        // Seized cargo may have paid a loan off with money to spare
+        let surplus: f32 = self.loans.iter().filter(|loan| loan.balance < 0.0).map(|loan| -loan.balance).sum();
+        inventory.money += surplus as i32;
+        self.loans.retain(|loan| loan.balance >= 0.01);
+
+        penalties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Difficulty, GameRules};
+
+    #[test]
+    fn overpaying_a_fractional_loan_charges_no_more_than_it_clears() {
+        let rules = GameRules::preset(Difficulty::Normal);
+        let mut bank = Bank::new(rules.prices);
+        let mut inventory = Inventory::new(&rules);
+        inventory.money = 100;
+        bank.loans.push(Loan { balance: 10.3, due_at: 60.0, missed_payments: 0 });
+
+        let paid = bank.repay(&mut inventory, 100);
+
+        assert_eq!(paid, Ok(10));
+        assert_eq!(inventory.money, 90);
+        assert!(bank.loans.is_empty());
+    }
+
+    #[test]
+    fn refused_requests_say_why() {
+        let rules = GameRules::preset(Difficulty::Normal);
+        let mut bank = Bank::new(rules.prices);
+        let mut inventory = Inventory::new(&rules);
+        inventory.money = 20;
+
+        assert_eq!(bank.repay(&mut inventory, 10), Err(BankError::NoDebt));
+        assert_eq!(bank.withdraw(&mut inventory, 10), Err(BankError::NoSavings));
+        assert_eq!(
+            bank.deposit(&mut inventory, 50),
+            Err(BankError::Inventory(InventoryError::InsufficientFunds { cost: 50, available: 20 }))
+        );
+        let available = bank.available_credit(&inventory);
+        assert_eq!(
+            bank.borrow(&mut inventory, available + 1, 0.0),
+            Err(BankError::OverCreditLimit { requested: available + 1, available })
+        );
+        assert_eq!(inventory.money, 20);
+    }
+}
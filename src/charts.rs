@@ -0,0 +1,95 @@
+use macroquad::prelude::*;
+use crate::economy::{PriceHistory, ProductType};
+use crate::models::SolarSystem;
+
+const PRICE_COLOR: Color = YELLOW;
+const STOCK_COLOR: Color = Color::new(0.4, 0.7, 1.0, 0.7);
+const INDEX_ROW_HEIGHT: f32 = 18.0;
+
+// Connect the values with straight segments, scaled between `low` and `high`
+fn draw_series(values: &[f32], low: f32, high: f32, area: Rect, color: Color) {
+    if values.len() < 2 {
+        return;
+    }
+    let range = (high - low).max(1.0);
+    let step = area.w / (values.len() - 1) as f32;
+    let point = |i: usize| Vec2::new(area.x + i as f32 * step, area.bottom() - (values[i] - low) / range * area.h);
+
+    for i in 1..values.len() {
+        let (a, b) = (point(i - 1), point(i));
+        draw_line(a.x, a.y, b.x, b.y, 2.0, color);
+    }
+}
+
+// Price (with min, max and average) and stock for one product at one market
+pub fn draw_price_chart(history: Option<&PriceHistory>, area: Rect) {
+    let Rect { x, y, w, h } = area;
+    draw_rectangle(x, y, w, h, Color::new(0.05, 0.05, 0.1, 1.0));
+    draw_rectangle_lines(x, y, w, h, 1.0, DARKGRAY);
+
+    let Some(history) = history.filter(|h| h.samples.len() >= 2) else {
+        draw_text("Collecting data...", x + 10.0, y + h / 2.0, 16.0, GRAY);
+        return;
+    };
+    let (Some(min), Some(max), Some(avg)) = (history.min_price(), history.max_price(), history.average_price()) else {
+        return;
+    };
+
+    // Pad the price axis so a flat line sits in the middle instead of on the edge
+    let low = min as f32 - 2.0;
+    let high = max as f32 + 2.0;
+
+    let avg_y = y + h - (avg - low) / (high - low) * h;
+    let mut dash_x = x;
+    while dash_x < x + w {
+        draw_line(dash_x, avg_y, (dash_x + 6.0).min(x + w), avg_y, 1.0, GRAY);
+        dash_x += 12.0;
+    }
+
+    let stock: Vec<f32> = history.samples.iter().map(|s| s.stock as f32).collect();
+    let max_stock = stock.iter().copied().fold(1.0, f32::max);
+    draw_series(&stock, 0.0, max_stock, area, STOCK_COLOR);
+
+    let prices: Vec<f32> = history.samples.iter().map(|s| s.price as f32).collect();
+    draw_series(&prices, low, high, area, PRICE_COLOR);
+
+    let current = history.samples.back().map(|s| s.price).unwrap_or(0);
+    draw_text(
+        &format!("Now ${}  Min ${}  Max ${}  Avg ${:.1}", current, min, max, avg),
+        x,
+        y + h + 18.0,
+        16.0,
+        WHITE,
+    );
+    draw_text("price", x + w - 90.0, y + 14.0, 14.0, PRICE_COLOR);
+    draw_text("stock", x + w - 45.0, y + 14.0, 14.0, STOCK_COLOR);
+}
+
+// Space the price index needs: a heading and one row per planet
+pub fn price_index_height(system: &SolarSystem) -> f32 {
+    20.0 + system.planets.len() as f32 * INDEX_ROW_HEIGHT
+}
+
+// Each planet's quote for a product relative to the planets' average (100 = average)
+pub fn draw_price_index(system: &SolarSystem, product: ProductType, x: f32, y: f32, w: f32) {
+    let average = system.average_price(product).max(1.0);
+    draw_text(&format!("Galactic price index - avg ${:.1}", average), x, y, 16.0, WHITE);
+
+    let bar_x = x + 150.0;
+    let bar_w = w - 230.0;
+    for (i, planet) in system.planets.iter().enumerate() {
+        let row_y = y + 20.0 + i as f32 * INDEX_ROW_HEIGHT;
+        let quote = planet.market.quote(product);
+        let index = quote as f32 / average * 100.0;
+
+        // Bars are centred on 100, stretching left when cheap and right when dear
+        let mid = bar_x + bar_w / 2.0;
+        let offset = ((index - 100.0) / 50.0).clamp(-1.0, 1.0) * bar_w / 2.0;
+        let color = if index >= 100.0 { Color::new(0.3, 0.8, 0.3, 0.8) } else { Color::new(0.8, 0.3, 0.3, 0.8) };
+        draw_rectangle(mid.min(mid + offset), row_y - 10.0, offset.abs(), 10.0, color);
+        draw_line(mid, row_y - 12.0, mid, row_y, 1.0, GRAY);
+
+        draw_text(&planet.name, x, row_y, 15.0, LIGHTGRAY);
+        draw_text(&format!("${} ({:.0})", quote, index), bar_x + bar_w + 8.0, row_y, 15.0, LIGHTGRAY);
+    }
+}
@@ -0,0 +1,130 @@
+use macroquad::prelude::*;
+use crate::input::{is_bindable, key_name, Action, Bindings};
+use crate::scene::{Context, Scene, Transition};
+
+// Overlay for rebinding the keys of every action. Pushed over the settings
+// screen, which saves the bindings when it closes.
+pub struct ControlsScreen {
+    selected: usize,
+    scroll: usize,
+    rebinding: bool, // Waiting for the new key of the selected action
+    status: Option<String>,
+}
+
+impl ControlsScreen {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            scroll: 0,
+            rebinding: false,
+            status: None,
+        }
+    }
+
+    fn conflict_warning(&self, bindings: &Bindings, action: Action) -> Option<String> {
+        let conflicts = bindings.conflicts(action);
+        if conflicts.is_empty() {
+            return None;
+        }
+        let names: Vec<&str> = conflicts.iter().map(|other| other.name()).collect();
+        Some(format!("Also bound to: {}", names.join(", ")))
+    }
+}
+
+impl Scene for ControlsScreen {
+    fn handle_input(&mut self, ctx: &mut Context) -> Transition {
+        let bindings = &mut ctx.settings.bindings;
+        let actions = Action::all();
+        let action = actions[self.selected];
+        if self.rebinding {
+            // Escape always cancels, so Back can't be bound away for good
+            if is_key_pressed(KeyCode::Escape) {
+                self.rebinding = false;
+            } else if let Some(key) = get_last_key_pressed() {
+                if is_bindable(key) {
+                    bindings.bind(action, key);
+                    self.rebinding = false;
+                    self.status = self.conflict_warning(bindings, action);
+                } else {
+                    self.status = Some(format!("{} can't be bound", key_name(key)));
+                }
+            }
+            return Transition::None;
+        }
+
+        if bindings.is_pressed(Action::MenuUp) && self.selected > 0 {
+            self.selected -= 1;
+            self.status = None;
+        }
+        if bindings.is_pressed(Action::MenuDown) && self.selected + 1 < actions.len() {
+            self.selected += 1;
+            self.status = None;
+        }
+        if bindings.is_pressed(Action::Confirm) {
+            self.rebinding = true;
+            self.status = None;
+        }
+        if bindings.is_pressed(Action::ResetBinding) {
+            bindings.reset(action);
+            self.status = self.conflict_warning(bindings, action);
+        }
+        Transition::pop_if(bindings.is_pressed(Action::Back))
+    }
+
+    fn draw(&mut self, ctx: &mut Context) {
+        let bindings = &ctx.settings.bindings;
+        let ui_x = screen_width() / 2.0 - 300.0;
+        let ui_y = 60.0;
+        let width = 600.0;
+        let height = screen_height() - 120.0;
+        draw_rectangle(ui_x, ui_y, width, height, Color::new(0.0, 0.0, 0.05, 0.95));
+        draw_rectangle_lines(ui_x, ui_y, width, height, 2.0, SKYBLUE);
+        draw_text("CONTROLS", ui_x + 20.0, ui_y + 35.0, 30.0, YELLOW);
+
+        // Keep the selected row in view
+        let actions = Action::all();
+        let rows = ((height - 130.0) / 24.0).max(1.0) as usize;
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + rows {
+            self.scroll = self.selected + 1 - rows;
+        }
+
+        for (row, action) in actions.iter().enumerate().skip(self.scroll).take(rows) {
+            let y = ui_y + 75.0 + (row - self.scroll) as f32 * 24.0;
+            let is_selected = row == self.selected;
+            if is_selected {
+                draw_rectangle(ui_x + 10.0, y - 17.0, width - 20.0, 23.0, Color::new(0.2, 0.3, 0.5, 0.5));
+            }
+            let color = if is_selected { WHITE } else { LIGHTGRAY };
+            draw_text(action.name(), ui_x + 20.0, y, 18.0, color);
+            let keys = if is_selected && self.rebinding { "press a key...".to_string() } else { bindings.describe(*action) };
+            let key_color = if !bindings.conflicts(*action).is_empty() {
+                ORANGE
+            } else if is_selected {
+                YELLOW
+            } else {
+                color
+            };
+            draw_text(&keys, ui_x + 320.0, y, 18.0, key_color);
+        }
+
+        let footer_y = ui_y + height - 20.0;
+        if let Some(status) = &self.status {
+            draw_text(status, ui_x + 20.0, footer_y - 25.0, 16.0, ORANGE);
+        }
+        let hint = format!(
+            "{}/{} select  {} rebind  {} default  {} back",
+            bindings.label(Action::MenuUp),
+            bindings.label(Action::MenuDown),
+            bindings.label(Action::Confirm),
+            bindings.label(Action::ResetBinding),
+            bindings.label(Action::Back),
+        );
+        draw_text(&hint, ui_x + 20.0, footer_y, 16.0, DARKGRAY);
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -1,22 +1,43 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use macroquad::rand;
+use crate::factory::Factory;
+use crate::rules::{GameRules, ProductPrices};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ProductType {
     Spice,
     Minerals,
     Biomatter,
+    RareOre,
+    Medicine,
+    LuxuryGoods,
 }
 
 impl ProductType {
-    pub fn name(&self) -> &str {
+    pub fn name(&self) -> &'static str {
         match self {
             ProductType::Spice => "Spice",
             ProductType::Minerals => "Minerals",
             ProductType::Biomatter => "Biomatter",
+            ProductType::RareOre => "Rare Ore",
+            ProductType::Medicine => "Medicine",
+            ProductType::LuxuryGoods => "Luxury Goods",
         }
     }
 
     pub fn all() -> Vec<ProductType> {
+        vec![
+            ProductType::Spice,
+            ProductType::Minerals,
+            ProductType::Biomatter,
+            ProductType::RareOre,
+            ProductType::Medicine,
+            ProductType::LuxuryGoods,
+        ]
+    }
+
+    // Goods that planets and moons produce themselves
+    pub fn raw_goods() -> Vec<ProductType> {
         vec![ProductType::Spice, ProductType::Minerals, ProductType::Biomatter]
     }
 }
@@ -26,6 +47,20 @@ pub const BUY_PRODUCT_PRICE:  i32 = 20;
 pub const SELL_PRODUCT_PRICE: i32 = 23;
 pub const FUEL_PRICE: i32 = 5;
 pub const FOOD_PRICE: i32 = 3;
+pub const RARE_ORE_SELL_PRICE: i32 = 60;
+pub const MEDICINE_SELL_PRICE: i32 = 50;
+pub const LUXURY_GOODS_SELL_PRICE: i32 = 70;
+pub const MINING_LASER_PRICE: i32 = 150;
+pub const HULL_REPAIR_PRICE: i32 = 2;              // Per hull point restored
+pub const INSURANCE_PRICE: i32 = 80;
+pub const INSURANCE_PAYOUT: i32 = 200;
+pub const SHIP_PRICE: i32 = 300;                   // Extra trade ship for the fleet
+pub const SHIP_DELIVERY_FUEL: f32 = 30.0;          // Fuel and food a bought ship comes with
+pub const SHIP_DELIVERY_FOOD: i32 = 15;
+pub const FACTORY_PRICE: i32 = 400;
+pub const FACTORY_UPKEEP_PER_MINUTE: f32 = 20.0;
+pub const SMUGGLER_COMPARTMENT_PRICE: i32 = 250;
+pub const CONTRABAND_FINE_PER_UNIT: i32 = 30;
 
 // === GAMEPLAY TUNING CONSTANTS ===
 // Starting resources
@@ -33,13 +68,430 @@ pub const STARTING_FUEL: f32 = 50.0;
 pub const STARTING_FOOD: i32 = 30;
 pub const STARTING_MONEY: i32 = 100;
 
+// Cargo hold
+pub const CARGO_CAPACITY: i32 = 100;
+
+// Hull and damage
+pub const MAX_HULL: f32 = 100.0;
+pub const COLLISION_DAMAGE: f32 = 15.0;            // Hitting a planet, moon or the star
+pub const ASTEROID_COLLISION_DAMAGE: f32 = 5.0;
+pub const COLLISION_COOLDOWN: f32 = 0.5;           // Seconds of grace after an impact
+pub const STAR_HEAT_RADIUS_FACTOR: f32 = 2.0;      // Heat damage starts at this many star radii
+pub const STAR_HEAT_DAMAGE_PER_SECOND: f32 = 12.0; // At the star's surface, fading to 0 at the edge
+pub const DAMAGE_DEGRADES_SHIP: bool = true;       // Damaged hulls fly slower and hold less cargo
+
 // Consumption rates
 pub const FUEL_CONSUMPTION_PER_SECOND: f32 = 0.5;  // Fuel consumed per second while moving
 pub const FOOD_CONSUMPTION_INTERVAL: f32 = 5.0;    // Seconds between food consumption
 pub const FOOD_CONSUMED_PER_INTERVAL: i32 = 1;     // Food consumed per interval
+pub const LOW_FUEL_WARNING: f32 = 15.0;            // Warn the player when fuel drops below this
+pub const LOW_FOOD_WARNING: i32 = 5;               // ...and food below this
+
+// Time controls: how many times faster than normal the simulation can run
+pub const TIME_SCALES: [f32; 3] = [1.0, 2.0, 4.0];
 
 // Buying amounts
 pub const FUEL_BUY_AMOUNT: f32 = 10.0;             // Fuel units received per purchase
+pub const MAX_PRODUCT_BUY_QUANTITY: i32 = 10;      // Most units of a product bought in one click
+
+// Mining
+pub const MINING_RANGE: f32 = 60.0;                // Max distance from ship to asteroid surface
+pub const MINING_TIME: f32 = 1.5;                  // Seconds of laser time per unit of ore
+pub const MINING_WEAR_PER_UNIT: f32 = 0.04;        // Laser condition lost per unit mined
+pub const RARE_ORE_CHANCE: f32 = 0.1;              // Chance a unit from a common asteroid is rare ore
+pub const RARE_ASTEROID_CHANCE: f32 = 0.1;         // Chance a belt asteroid is rich in rare ore
+
+// Banking (rates are per minute of game time)
+pub const LOAN_AMOUNT: i32 = 100;                  // Borrowed per request
+pub const LOAN_INTEREST_RATE: f32 = 0.05;
+pub const SAVINGS_INTEREST_RATE: f32 = 0.01;
+pub const DEPOSIT_AMOUNT: i32 = 50;                // Deposited or withdrawn per request
+pub const LOAN_TERM: f32 = 180.0;                  // Seconds until a new loan is due
+pub const LOAN_GRACE_PERIOD: f32 = 60.0;           // Extra time given after a missed payment
+pub const LOAN_LATE_FEE: i32 = 25;
+pub const CREDIT_LIMIT_FACTOR: f32 = 1.5;          // Max debt as a multiple of net worth
+pub const MIN_CREDIT_LIMIT: i32 = 100;
+pub const BOUNTY_HUNTER_STRIKES: u32 = 2;          // Missed payments before hunters are sent
+
+// Faction reputation, from -MAX_REPUTATION (hostile) to MAX_REPUTATION (allied)
+pub const MAX_REPUTATION: f32 = 100.0;
+pub const REPUTATION_PER_TRADE_UNIT: f32 = 0.25;   // Gained per unit bought or sold in a faction's market
+pub const REPUTATION_PER_MISSED_PAYMENT: f32 = 15.0; // Lost with every faction when a loan payment is missed
+pub const REPUTATION_RECOVERY_PER_MINUTE: f32 = 3.0; // Bad reputation fades back toward neutral this fast
+
+// Levies charged by every market, before faction adjustments
+pub const MIN_DOCKING_FEE: i32 = 2;
+pub const MAX_DOCKING_FEE: i32 = 8;
+pub const MAX_SALES_TAX: f32 = 0.08;               // On every unit bought or sold
+pub const MAX_TARIFF: f32 = 0.15;                  // Per-product import/export tariff
+
+// Smuggling
+pub const CUSTOMS_SCAN_CHANCE: f32 = 0.4;          // Chance a landing ship's hold is scanned
+pub const SMUGGLER_COMPARTMENT_CAPACITY: i32 = 10; // Units of contraband a compartment keeps out of sight
+pub const COMPARTMENT_DETECTION_CHANCE: f32 = 0.1; // Chance a scan finds the compartment anyway
+pub const CONTRABAND_PREMIUM: f32 = 1.8;           // Black market price multiplier for illegal goods
+pub const REPUTATION_PER_SMUGGLING_BUST: f32 = 20.0;
+
+// Market stock
+pub const MARKET_STARTING_STOCK: i32 = 40;         // Units of the local product on hand at start
+pub const MARKET_MAX_STOCK: i32 = 80;              // Production stops once this much is stockpiled
+pub const MARKET_PRODUCTION_INTERVAL: f32 = 2.0;   // Seconds to produce one unit of the local product
+pub const MARKET_CONSUMPTION_INTERVAL: f32 = 4.0;  // Seconds for locals to consume one unit of each import
+pub const MARKET_IMPORT_DEMAND: i32 = 10;          // Stock of an import at which it fetches its base price
+
+// Supply and demand: prices move with stock relative to a reference level
+pub const PRICE_ELASTICITY: f32 = 0.5;             // Price change when stock is 100% off the reference
+pub const MIN_PRICE_FACTOR: f32 = 0.6;
+pub const MAX_PRICE_FACTOR: f32 = 1.5;
+
+// Price history
+pub const PRICE_SAMPLE_INTERVAL: f32 = 2.0;        // Seconds between samples
+pub const PRICE_HISTORY_LENGTH: usize = 120;       // Samples kept per product (4 minutes)
+
+// Apply a multiplier (faction attitude and the like) to a price
+pub fn scale_price(price: i32, factor: f32) -> i32 {
+    (price as f32 * factor).round() as i32
+}
+
+// Fees, tariffs and taxes a market charges on top of its prices
+#[derive(Debug, Clone)]
+pub struct TaxSchedule {
+    pub docking_fee: i32,
+    pub sales_tax: f32,
+    pub import_tariffs: HashMap<ProductType, f32>, // On goods sold into this market
+    pub export_tariffs: HashMap<ProductType, f32>, // On goods bought out of it
+}
+
+impl TaxSchedule {
+    // Markets tend to protect what they make from imports and skim a little off what leaves
+    pub fn random(produces: Option<ProductType>) -> Self {
+        let mut import_tariffs = HashMap::new();
+        let mut export_tariffs = HashMap::new();
+        for product in ProductType::all() {
+            if produces == Some(product) {
+                if rand::gen_range(0.0, 1.0) < 0.5 {
+                    export_tariffs.insert(product, rand::gen_range(0.02, MAX_TARIFF * 0.5));
+                }
+            } else if rand::gen_range(0.0, 1.0) < 0.4 {
+                import_tariffs.insert(product, rand::gen_range(0.02, MAX_TARIFF));
+            }
+        }
+        Self {
+            docking_fee: rand::gen_range(MIN_DOCKING_FEE, MAX_DOCKING_FEE + 1),
+            sales_tax: rand::gen_range(0.0, MAX_SALES_TAX),
+            import_tariffs,
+            export_tariffs,
+        }
+    }
+
+    pub fn import_tariff(&self, product: ProductType) -> f32 {
+        self.import_tariffs.get(&product).copied().unwrap_or(0.0)
+    }
+
+    pub fn export_tariff(&self, product: ProductType) -> f32 {
+        self.export_tariffs.get(&product).copied().unwrap_or(0.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+// How a trader is treated at a market: the local faction's price and tariff
+// adjustments, or black market terms that skip customs and the tax office
+#[derive(Debug, Clone, Copy)]
+pub struct TradeTerms {
+    pub price_factor: f32,
+    pub tariff_factor: f32,
+    pub black_market: bool,
+}
+
+impl TradeTerms {
+    pub fn standard() -> Self {
+        Self { price_factor: 1.0, tariff_factor: 1.0, black_market: false }
+    }
+}
+
+// Why a ship's hold or wallet couldn't cover a change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InventoryError {
+    InsufficientFunds { cost: i32, available: i32 },
+    HoldFull { needed: i32, available: i32 },
+    NotCarried { product: ProductType, requested: i32, carried: i32 },
+}
+
+impl InventoryError {
+    pub fn describe(&self) -> String {
+        match self {
+            InventoryError::InsufficientFunds { cost, available } => {
+                format!("Not enough money - costs ${}, you have ${}", cost, available)
+            }
+            InventoryError::HoldFull { needed, available } => {
+                format!("Cargo hold full - needs {} free, {} left", needed, available)
+            }
+            InventoryError::NotCarried { product, requested, carried } => {
+                format!("Not enough {} - wanted {}, carrying {}", product.name(), requested, carried)
+            }
+        }
+    }
+}
+
+// Why a trade was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeError {
+    Inventory(InventoryError),
+    OutOfStock { requested: i32, available: i32 },
+    IllegalGood(ProductType),
+    InvalidQuantity,
+}
+
+impl TradeError {
+    pub fn describe(&self) -> String {
+        match self {
+            TradeError::Inventory(err) => err.describe(),
+            TradeError::OutOfStock { requested, available } => {
+                format!("Out of stock - wanted {}, only {} left", requested, available)
+            }
+            TradeError::IllegalGood(product) => format!("{} is contraband here", product.name()),
+            TradeError::InvalidQuantity => "Nothing to trade".to_string(),
+        }
+    }
+}
+
+impl From<InventoryError> for TradeError {
+    fn from(err: InventoryError) -> Self {
+        TradeError::Inventory(err)
+    }
+}
+
+// A priced trade, split into the line items the ledger records
+#[derive(Debug, Clone, Copy)]
+pub struct TradeQuote {
+    pub side: TradeSide,
+    pub product: ProductType,
+    pub quantity: i32,
+    pub goods: i32,
+    pub tariff: i32,
+    pub tax: i32,
+}
+
+impl TradeQuote {
+    // What the buyer pays in all, or what the seller keeps
+    pub fn total(&self) -> i32 {
+        match self.side {
+            TradeSide::Buy => self.goods + self.tariff + self.tax,
+            TradeSide::Sell => self.goods - self.tariff - self.tax,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PriceSample {
+    pub price: i32,
+    pub stock: i32,
+}
+
+// Rolling window of a product's price and stock at one market
+#[derive(Debug, Clone, Default)]
+pub struct PriceHistory {
+    pub samples: VecDeque<PriceSample>,
+}
+
+impl PriceHistory {
+    pub fn record(&mut self, sample: PriceSample) {
+        if self.samples.len() == PRICE_HISTORY_LENGTH {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn min_price(&self) -> Option<i32> {
+        self.samples.iter().map(|s| s.price).min()
+    }
+
+    pub fn max_price(&self) -> Option<i32> {
+        self.samples.iter().map(|s| s.price).max()
+    }
+
+    pub fn average_price(&self) -> Option<f32> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        Some(self.samples.iter().map(|s| s.price as f32).sum::<f32>() / self.samples.len() as f32)
+    }
+}
+
+// A trade location's market: what it produces and what it has in stock
+#[derive(Debug, Clone)]
+pub struct Market {
+    pub produces: Option<ProductType>,
+    pub stock: HashMap<ProductType, i32>,
+    pub factories: Vec<Factory>,
+    pub history: HashMap<ProductType, PriceHistory>,
+    pub taxes: TaxSchedule,
+    pub prices: ProductPrices, // Base prices from the run's rules
+    production_timer: f32,
+    consumption_timer: f32,
+}
+
+impl Market {
+    pub fn new(produces: Option<ProductType>, prices: ProductPrices) -> Self {
+        let mut stock = HashMap::new();
+        if let Some(product) = produces {
+            stock.insert(product, MARKET_STARTING_STOCK);
+        }
+        Self {
+            produces,
+            stock,
+            factories: Vec::new(),
+            history: HashMap::new(),
+            taxes: TaxSchedule::random(produces),
+            prices,
+            production_timer: 0.0,
+            consumption_timer: 0.0,
+        }
+    }
+
+    pub fn stock_of(&self, product: ProductType) -> i32 {
+        self.stock.get(&product).copied().unwrap_or(0)
+    }
+
+    // Whether this market makes the product itself (directly or in a factory)
+    pub fn sells(&self, product: ProductType) -> bool {
+        self.produces == Some(product) || self.factories.iter().any(|f| f.kind.output() == product)
+    }
+
+    // Everything that can be bought here, local product first
+    pub fn for_sale(&self) -> Vec<ProductType> {
+        let mut products: Vec<ProductType> = self.produces.into_iter().collect();
+        for factory in &self.factories {
+            if !products.contains(&factory.kind.output()) {
+                products.push(factory.kind.output());
+            }
+        }
+        products
+    }
+
+    // Scarce goods cost more, plentiful ones less. Local goods are measured
+    // against the starting stockpile, imports against a small demand level.
+    fn price_factor(&self, product: ProductType, stock: i32) -> f32 {
+        let reference = if self.sells(product) { MARKET_STARTING_STOCK } else { MARKET_IMPORT_DEMAND } as f32;
+        let shortage = (reference - stock as f32) / reference;
+        (1.0 + PRICE_ELASTICITY * shortage).clamp(MIN_PRICE_FACTOR, MAX_PRICE_FACTOR)
+    }
+
+    // Price of one unit when the market holds `stock` of it
+    fn unit_price(&self, side: TradeSide, product: ProductType, stock: i32) -> i32 {
+        let base = match side {
+            TradeSide::Buy => self.prices.buy_price(product),
+            TradeSide::Sell => self.prices.sell_price(product),
+        };
+        ((base as f32 * self.price_factor(product, stock)).round() as i32).max(1)
+    }
+
+    // What the player pays here for one unit
+    pub fn buy_price(&self, product: ProductType) -> i32 {
+        self.unit_price(TradeSide::Buy, product, self.stock_of(product))
+    }
+
+    // What the market pays the player for one unit
+    pub fn sell_price(&self, product: ProductType) -> i32 {
+        self.unit_price(TradeSide::Sell, product, self.stock_of(product))
+    }
+
+    // Price a trade unit by unit, so big orders move the price as they go,
+    // then add this market's tariff and sales tax on top
+    pub fn quote_trade(&self, side: TradeSide, product: ProductType, quantity: i32, terms: TradeTerms) -> TradeQuote {
+        let stock = self.stock_of(product);
+        let goods: i32 = (0..quantity)
+            .map(|i| {
+                let stock_then = match side {
+                    TradeSide::Buy => stock - i,
+                    TradeSide::Sell => stock + i,
+                };
+                scale_price(self.unit_price(side, product, stock_then), terms.price_factor)
+            })
+            .sum();
+        let tariff_rate = match side {
+            TradeSide::Buy => self.taxes.export_tariff(product),
+            TradeSide::Sell => self.taxes.import_tariff(product),
+        };
+        let tax_rate = if terms.black_market { 0.0 } else { self.taxes.sales_tax };
+        TradeQuote {
+            side,
+            product,
+            quantity,
+            goods,
+            tariff: scale_price(goods, tariff_rate * terms.tariff_factor),
+            tax: scale_price(goods, tax_rate),
+        }
+    }
+
+    // The price that matters to a trader here: asking price for what the
+    // market makes, bid price for everything else
+    pub fn quote(&self, product: ProductType) -> i32 {
+        if self.sells(product) {
+            self.buy_price(product)
+        } else {
+            self.sell_price(product)
+        }
+    }
+
+    pub fn record_prices(&mut self) {
+        for product in ProductType::all() {
+            let sample = PriceSample {
+                price: self.quote(product),
+                stock: self.stock_of(product),
+            };
+            self.history.entry(product).or_default().record(sample);
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        // Produce the local product up to the stockpile limit
+        if let Some(product) = self.produces {
+            self.production_timer += dt;
+            if self.production_timer >= MARKET_PRODUCTION_INTERVAL {
+                self.production_timer = 0.0;
+                let amount = self.stock.entry(product).or_insert(0);
+                *amount = (*amount + 1).min(MARKET_MAX_STOCK);
+            }
+        }
+
+        for factory in &mut self.factories {
+            factory.update(dt, &mut self.stock, &self.prices);
+        }
+
+        // Imported goods are slowly used up by the locals
+        self.consumption_timer += dt;
+        if self.consumption_timer >= MARKET_CONSUMPTION_INTERVAL {
+            self.consumption_timer = 0.0;
+            let local: Vec<ProductType> = self.for_sale();
+            for (product, amount) in self.stock.iter_mut() {
+                if !local.contains(product) {
+                    *amount = (*amount - 1).max(0);
+                }
+            }
+            self.stock.retain(|_, amount| *amount > 0);
+        }
+    }
+
+    pub fn take(&mut self, product: ProductType, amount: i32) -> bool {
+        if let Some(current) = self.stock.get_mut(&product)
+            && *current >= amount
+        {
+            *current -= amount;
+            return true;
+        }
+        false
+    }
+
+    pub fn deliver(&mut self, product: ProductType, amount: i32) {
+        *self.stock.entry(product).or_insert(0) += amount;
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Inventory {
@@ -47,15 +499,29 @@ pub struct Inventory {
     pub fuel: f32,
     pub food: i32,
     pub money: i32,
+    pub capacity: i32,
 }
 
 impl Inventory {
-    pub fn new() -> Self {
+    pub fn new(rules: &GameRules) -> Self {
         Self {
             cargo: HashMap::new(),
-            fuel: STARTING_FUEL,
-            food: STARTING_FOOD,
-            money: STARTING_MONEY,
+            fuel: rules.starting_fuel,
+            food: rules.starting_food,
+            money: rules.starting_money,
+            capacity: CARGO_CAPACITY,
+        }
+    }
+
+    // A bought ship's hold: fuel and food to reach its first stop, and no
+    // money, since the fleet shares one purse
+    pub fn delivered() -> Self {
+        Self {
+            cargo: HashMap::new(),
+            fuel: SHIP_DELIVERY_FUEL,
+            food: SHIP_DELIVERY_FOOD,
+            money: 0,
+            capacity: CARGO_CAPACITY,
         }
     }
 
@@ -64,47 +530,345 @@ impl Inventory {
     }
 
     pub fn available_space(&self) -> i32 {
-        100 - self.total_cargo()
+        self.capacity - self.total_cargo()
+    }
+
+    pub fn carried(&self, product: ProductType) -> i32 {
+        self.cargo.get(&product).copied().unwrap_or(0)
     }
 
-    pub fn add_cargo(&mut self, product: ProductType, amount: i32) -> bool {
-        if self.available_space() >= amount {
-            *self.cargo.entry(product).or_insert(0) += amount;
-            true
+    pub fn ensure_space(&self, needed: i32) -> Result<(), InventoryError> {
+        let available = self.available_space();
+        if available < needed {
+            return Err(InventoryError::HoldFull { needed, available });
+        }
+        Ok(())
+    }
+
+    pub fn add_cargo(&mut self, product: ProductType, amount: i32) -> Result<(), InventoryError> {
+        self.ensure_space(amount)?;
+        *self.cargo.entry(product).or_insert(0) += amount;
+        Ok(())
+    }
+
+    pub fn remove_cargo(&mut self, product: ProductType, amount: i32) -> Result<(), InventoryError> {
+        let carried = self.carried(product);
+        if carried < amount {
+            return Err(InventoryError::NotCarried { product, requested: amount, carried });
+        }
+        if carried == amount {
+            self.cargo.remove(&product);
         } else {
-            false
+            self.cargo.insert(product, carried - amount);
         }
+        Ok(())
     }
 
-    pub fn remove_cargo(&mut self, product: ProductType, amount: i32) -> bool {
-        if let Some(current) = self.cargo.get_mut(&product) {
-            if *current >= amount {
-                *current -= amount;
-                if *current == 0 {
-                    self.cargo.remove(&product);
-                }
-                return true;
-            }
+    // Pay for something, or explain why we can't
+    pub fn spend(&mut self, cost: i32) -> Result<(), InventoryError> {
+        if self.money < cost {
+            return Err(InventoryError::InsufficientFunds { cost, available: self.money });
         }
-        false
+        self.money -= cost;
+        Ok(())
     }
+}
 
-    pub fn sell_all_cargo(&mut self, planet_product: ProductType) -> i32 {
-        let mut total_earned = 0;
+// Every trade between a trader and a port goes through here. Each method
+// validates the whole trade first, and only then moves stock, cargo and money,
+// so a refused trade leaves everything as it was.
+pub struct TradeService<'a> {
+    market: &'a mut Market,
+    inventory: &'a mut Inventory,
+    rules: &'a GameRules,
+}
 
-        // Sell all cargo except the planet's own product
-        let products_to_sell: Vec<ProductType> = self.cargo.keys()
-            .filter(|&&p| p != planet_product)
-            .copied()
+impl<'a> TradeService<'a> {
+    pub fn new(market: &'a mut Market, inventory: &'a mut Inventory, rules: &'a GameRules) -> Self {
+        Self { market, inventory, rules }
+    }
+
+    pub fn inventory(&self) -> &Inventory {
+        self.inventory
+    }
+
+    pub fn buy(&mut self, product: ProductType, quantity: i32, terms: TradeTerms) -> Result<TradeQuote, TradeError> {
+        if quantity <= 0 {
+            return Err(TradeError::InvalidQuantity);
+        }
+        if terms.black_market {
+            return Err(TradeError::IllegalGood(product));
+        }
+        let available = self.market.stock_of(product);
+        if available < quantity {
+            return Err(TradeError::OutOfStock { requested: quantity, available });
+        }
+        self.inventory.ensure_space(quantity)?;
+        let quote = self.market.quote_trade(TradeSide::Buy, product, quantity, terms);
+        self.inventory.spend(quote.total())?;
+
+        self.market.take(product, quantity);
+        self.inventory.add_cargo(product, quantity)?;
+        Ok(quote)
+    }
+
+    pub fn sell(&mut self, product: ProductType, quantity: i32, terms: TradeTerms) -> Result<TradeQuote, TradeError> {
+        if quantity <= 0 {
+            return Err(TradeError::InvalidQuantity);
+        }
+        self.inventory.remove_cargo(product, quantity)?;
+        let quote = self.market.quote_trade(TradeSide::Sell, product, quantity, terms);
+        self.market.deliver(product, quantity);
+        self.inventory.money += quote.total();
+        Ok(quote)
+    }
+
+    // Sell all cargo except what the market makes itself, on the terms
+    // given for each product. Returns the quote for each sale.
+    pub fn sell_all(&mut self, terms: impl Fn(ProductType) -> TradeTerms) -> Vec<TradeQuote> {
+        let products_to_sell: Vec<(ProductType, i32)> = self.inventory.cargo
+            .iter()
+            .filter(|&(&p, _)| !self.market.sells(p))
+            .map(|(&p, &amount)| (p, amount))
             .collect();
 
-        for product in products_to_sell {
-            if let Some(amount) = self.cargo.remove(&product) {
-                total_earned += amount * SELL_PRODUCT_PRICE;
-            }
+        products_to_sell
+            .into_iter()
+            .filter_map(|(product, amount)| self.sell(product, amount, terms(product)).ok())
+            .collect()
+    }
+
+    // The biggest order of a product that fits the hold, the stock and the
+    // budget while keeping `reserve` in hand
+    pub fn affordable_quantity(&self, product: ProductType, terms: TradeTerms, reserve: i32) -> i32 {
+        let mut quantity = self.inventory.available_space().min(self.market.stock_of(product));
+        while quantity > 0
+            && self.market.quote_trade(TradeSide::Buy, product, quantity, terms).total() + reserve > self.inventory.money
+        {
+            quantity -= 1;
         }
+        quantity.max(0)
+    }
+
+    // Fuel and food come from the port's supply depot, not the market
+    pub fn buy_fuel(&mut self) -> Result<i32, TradeError> {
+        self.inventory.ensure_space(FUEL_BUY_AMOUNT.ceil() as i32)?;
+        self.inventory.spend(self.rules.fuel_price)?;
+        self.inventory.fuel += FUEL_BUY_AMOUNT;
+        Ok(self.rules.fuel_price)
+    }
+
+    pub fn buy_food(&mut self) -> Result<i32, TradeError> {
+        self.inventory.ensure_space(1)?;
+        self.inventory.spend(self.rules.food_price)?;
+        self.inventory.food += 1;
+        Ok(self.rules.food_price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Difficulty;
+
+    fn rules() -> GameRules {
+        GameRules::preset(Difficulty::Normal)
+    }
+
+    // A market with no taxes or tariffs, so quotes are just the goods
+    fn untaxed_market(produces: ProductType) -> Market {
+        let mut market = Market::new(Some(produces), rules().prices);
+        market.taxes = TaxSchedule {
+            docking_fee: 0,
+            sales_tax: 0.0,
+            import_tariffs: HashMap::new(),
+            export_tariffs: HashMap::new(),
+        };
+        market
+    }
+
+    fn empty_hold(money: i32) -> Inventory {
+        Inventory { cargo: HashMap::new(), fuel: 0.0, food: 0, money, capacity: CARGO_CAPACITY }
+    }
+
+    #[test]
+    fn buy_without_the_money_is_refused() {
+        let rules = rules();
+        let mut market = untaxed_market(ProductType::Spice);
+        let mut inventory = empty_hold(5);
+        let cost = market.quote_trade(TradeSide::Buy, ProductType::Spice, 1, TradeTerms::standard()).total();
+
+        let result = TradeService::new(&mut market, &mut inventory, &rules).buy(ProductType::Spice, 1, TradeTerms::standard());
+
+        assert_eq!(result.unwrap_err(), TradeError::Inventory(InventoryError::InsufficientFunds { cost, available: 5 }));
+        assert_eq!(market.stock_of(ProductType::Spice), MARKET_STARTING_STOCK);
+        assert_eq!(inventory.money, 5);
+        assert!(inventory.cargo.is_empty());
+    }
+
+    #[test]
+    fn buy_beyond_the_stock_is_refused() {
+        let rules = rules();
+        let mut market = untaxed_market(ProductType::Spice);
+        let mut inventory = empty_hold(10_000);
+
+        let result = TradeService::new(&mut market, &mut inventory, &rules).buy(
+            ProductType::Spice,
+            MARKET_STARTING_STOCK + 1,
+            TradeTerms::standard(),
+        );
+
+        assert_eq!(
+            result.unwrap_err(),
+            TradeError::OutOfStock { requested: MARKET_STARTING_STOCK + 1, available: MARKET_STARTING_STOCK }
+        );
+        assert_eq!(inventory.money, 10_000);
+    }
+
+    #[test]
+    fn buy_into_a_full_hold_is_refused() {
+        let rules = rules();
+        let mut market = untaxed_market(ProductType::Spice);
+        let mut inventory = empty_hold(10_000);
+        inventory.capacity = 5;
+
+        let result = TradeService::new(&mut market, &mut inventory, &rules).buy(ProductType::Spice, 6, TradeTerms::standard());
+
+        assert_eq!(result.unwrap_err(), TradeError::Inventory(InventoryError::HoldFull { needed: 6, available: 5 }));
+        assert_eq!(inventory.money, 10_000);
+        assert_eq!(market.stock_of(ProductType::Spice), MARKET_STARTING_STOCK);
+    }
+
+    #[test]
+    fn buy_of_nothing_or_on_the_black_market_is_refused() {
+        let rules = rules();
+        let mut market = untaxed_market(ProductType::Spice);
+        let mut inventory = empty_hold(10_000);
+        let black_market = TradeTerms { black_market: true, ..TradeTerms::standard() };
+        let mut trade = TradeService::new(&mut market, &mut inventory, &rules);
+
+        assert_eq!(trade.buy(ProductType::Spice, 0, TradeTerms::standard()).unwrap_err(), TradeError::InvalidQuantity);
+        assert_eq!(trade.buy(ProductType::Spice, 1, black_market).unwrap_err(), TradeError::IllegalGood(ProductType::Spice));
+    }
+
+    #[test]
+    fn buy_moves_stock_cargo_and_money() {
+        let rules = rules();
+        let mut market = untaxed_market(ProductType::Spice);
+        let mut inventory = empty_hold(1_000);
+
+        let quote = TradeService::new(&mut market, &mut inventory, &rules)
+            .buy(ProductType::Spice, 3, TradeTerms::standard())
+            .unwrap();
+
+        assert_eq!(inventory.money, 1_000 - quote.total());
+        assert_eq!(inventory.carried(ProductType::Spice), 3);
+        assert_eq!(market.stock_of(ProductType::Spice), MARKET_STARTING_STOCK - 3);
+    }
+
+    #[test]
+    fn sell_of_cargo_not_carried_is_refused() {
+        let rules = rules();
+        let mut market = untaxed_market(ProductType::Spice);
+        let mut inventory = empty_hold(0);
+        inventory.add_cargo(ProductType::Minerals, 2).unwrap();
+
+        let result = TradeService::new(&mut market, &mut inventory, &rules).sell(ProductType::Minerals, 3, TradeTerms::standard());
+
+        assert_eq!(
+            result.unwrap_err(),
+            TradeError::Inventory(InventoryError::NotCarried { product: ProductType::Minerals, requested: 3, carried: 2 })
+        );
+        assert_eq!(inventory.carried(ProductType::Minerals), 2);
+        assert_eq!(market.stock_of(ProductType::Minerals), 0);
+    }
+
+    #[test]
+    fn affordable_quantity_is_capped_by_money_space_and_stock() {
+        let rules = rules();
+        let terms = TradeTerms::standard();
+        let mut market = untaxed_market(ProductType::Spice);
+
+        let mut broke = empty_hold(0);
+        assert_eq!(TradeService::new(&mut market, &mut broke, &rules).affordable_quantity(ProductType::Spice, terms, 0), 0);
+
+        // Exactly enough for four units on top of the reserve
+        let mut budget = empty_hold(market.quote_trade(TradeSide::Buy, ProductType::Spice, 4, terms).total() + 10);
+        assert_eq!(TradeService::new(&mut market, &mut budget, &rules).affordable_quantity(ProductType::Spice, terms, 10), 4);
+
+        let mut small_hold = empty_hold(10_000);
+        small_hold.capacity = 3;
+        assert_eq!(TradeService::new(&mut market, &mut small_hold, &rules).affordable_quantity(ProductType::Spice, terms, 0), 3);
+
+        market.stock.insert(ProductType::Spice, 2);
+        let mut rich = empty_hold(10_000);
+        assert_eq!(TradeService::new(&mut market, &mut rich, &rules).affordable_quantity(ProductType::Spice, terms, 0), 2);
+    }
+
+    #[test]
+    fn buy_fuel_charges_the_fuel_price_or_explains_why_not() {
+        let rules = rules();
+        let mut market = untaxed_market(ProductType::Spice);
+
+        let mut inventory = empty_hold(rules.fuel_price);
+        assert_eq!(TradeService::new(&mut market, &mut inventory, &rules).buy_fuel(), Ok(rules.fuel_price));
+        assert_eq!(inventory.money, 0);
+        assert_eq!(inventory.fuel, FUEL_BUY_AMOUNT);
+
+        assert_eq!(
+            TradeService::new(&mut market, &mut inventory, &rules).buy_fuel(),
+            Err(TradeError::Inventory(InventoryError::InsufficientFunds { cost: rules.fuel_price, available: 0 }))
+        );
+
+        let mut full = empty_hold(100);
+        full.capacity = 5;
+        assert_eq!(
+            TradeService::new(&mut market, &mut full, &rules).buy_fuel(),
+            Err(TradeError::Inventory(InventoryError::HoldFull { needed: FUEL_BUY_AMOUNT.ceil() as i32, available: 5 }))
+        );
+        assert_eq!(full.money, 100);
+    }
+
+    #[test]
+    fn buy_food_charges_the_food_price_or_explains_why_not() {
+        let rules = rules();
+        let mut market = untaxed_market(ProductType::Spice);
+
+        let mut inventory = empty_hold(rules.food_price);
+        assert_eq!(TradeService::new(&mut market, &mut inventory, &rules).buy_food(), Ok(rules.food_price));
+        assert_eq!(inventory.money, 0);
+        assert_eq!(inventory.food, 1);
+
+        assert_eq!(
+            TradeService::new(&mut market, &mut inventory, &rules).buy_food(),
+            Err(TradeError::Inventory(InventoryError::InsufficientFunds { cost: rules.food_price, available: 0 }))
+        );
+
+        let mut full = empty_hold(100);
+        full.capacity = 0;
+        assert_eq!(
+            TradeService::new(&mut market, &mut full, &rules).buy_food(),
+            Err(TradeError::Inventory(InventoryError::HoldFull { needed: 1, available: 0 }))
+        );
+    }
+
+    #[test]
+    fn inventory_refuses_changes_it_cannot_cover() {
+        let mut inventory = empty_hold(10);
+        inventory.capacity = 4;
+
+        assert_eq!(inventory.add_cargo(ProductType::Spice, 5), Err(InventoryError::HoldFull { needed: 5, available: 4 }));
+        assert_eq!(inventory.add_cargo(ProductType::Spice, 4), Ok(()));
+        assert_eq!(
+            inventory.remove_cargo(ProductType::Spice, 5),
+            Err(InventoryError::NotCarried { product: ProductType::Spice, requested: 5, carried: 4 })
+        );
+        assert_eq!(inventory.remove_cargo(ProductType::Spice, 4), Ok(()));
+        assert!(inventory.cargo.is_empty());
 
-        self.money += total_earned;
-        total_earned
+        assert_eq!(inventory.spend(11), Err(InventoryError::InsufficientFunds { cost: 11, available: 10 }));
+        assert_eq!(inventory.money, 10);
+        assert_eq!(inventory.spend(10), Ok(()));
+        assert_eq!(inventory.money, 0);
     }
 }
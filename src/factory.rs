@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use crate::economy::{ProductType, MARKET_MAX_STOCK, FACTORY_UPKEEP_PER_MINUTE};
+use crate::rules::ProductPrices;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FactoryType {
+    PharmaLab,
+    LuxuryWorkshop,
+}
+
+impl FactoryType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FactoryType::PharmaLab => "Pharma Lab",
+            FactoryType::LuxuryWorkshop => "Luxury Workshop",
+        }
+    }
+
+    pub fn all() -> Vec<FactoryType> {
+        vec![FactoryType::PharmaLab, FactoryType::LuxuryWorkshop]
+    }
+
+    // Units of each input consumed per production cycle
+    pub fn inputs(&self) -> &'static [(ProductType, i32)] {
+        match self {
+            FactoryType::PharmaLab => &[(ProductType::Biomatter, 2)],
+            FactoryType::LuxuryWorkshop => &[(ProductType::Minerals, 1), (ProductType::Spice, 1)],
+        }
+    }
+
+    pub fn output(&self) -> ProductType {
+        match self {
+            FactoryType::PharmaLab => ProductType::Medicine,
+            FactoryType::LuxuryWorkshop => ProductType::LuxuryGoods,
+        }
+    }
+
+    // Seconds per cycle
+    pub fn cycle_time(&self) -> f32 {
+        match self {
+            FactoryType::PharmaLab => 8.0,
+            FactoryType::LuxuryWorkshop => 10.0,
+        }
+    }
+
+    pub fn recipe(&self) -> String {
+        let inputs: Vec<String> = self.inputs().iter().map(|(p, n)| format!("{} {}", n, p.name())).collect();
+        format!("{} -> 1 {}", inputs.join(" + "), self.output().name())
+    }
+}
+
+// A player-owned factory on a planet. It buys its inputs from the planet's
+// market and sells its output back into it; the difference, minus upkeep,
+// builds up in `balance` until the owner collects it.
+#[derive(Debug, Clone)]
+pub struct Factory {
+    pub kind: FactoryType,
+    pub progress: f32,
+    pub running: bool,
+    pub balance: f32,
+}
+
+impl Factory {
+    pub fn new(kind: FactoryType) -> Self {
+        Self {
+            kind,
+            progress: 0.0,
+            running: false,
+            balance: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, dt: f32, stock: &mut HashMap<ProductType, i32>, prices: &ProductPrices) {
+        self.balance -= FACTORY_UPKEEP_PER_MINUTE * dt / 60.0;
+
+        if !self.running {
+            // Only start a cycle when all inputs are in stock and the market can take the output
+            let output = self.kind.output();
+            let has_inputs = self.kind.inputs().iter().all(|(p, n)| stock.get(p).copied().unwrap_or(0) >= *n);
+            let has_room = stock.get(&output).copied().unwrap_or(0) < MARKET_MAX_STOCK;
+            if !has_inputs || !has_room {
+                return;
+            }
+
+            for (product, amount) in self.kind.inputs() {
+                if let Some(current) = stock.get_mut(product) {
+                    *current -= amount;
+                }
+                self.balance -= (prices.buy_price(*product) * amount) as f32;
+            }
+            stock.retain(|_, amount| *amount > 0);
+            self.running = true;
+            self.progress = 0.0;
+        }
+
+        self.progress += dt;
+        if self.progress >= self.kind.cycle_time() {
+            let output = self.kind.output();
+            *stock.entry(output).or_insert(0) += 1;
+            self.balance += prices.buy_price(output) as f32;
+            self.running = false;
+            self.progress = 0.0;
+        }
+    }
+
+    // Take the accumulated balance, returns what changed hands (negative if the owner paid)
+    pub fn collect(&mut self, money: &mut i32) -> i32 {
+        let amount = if self.balance >= 0.0 {
+            self.balance.floor() as i32
+        } else {
+            // Settle as much of the running costs as the owner can afford
+            -((-self.balance).ceil() as i32).min(*money)
+        };
+        self.balance -= amount as f32;
+        *money += amount;
+        amount
+    }
+}
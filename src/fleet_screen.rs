@@ -0,0 +1,226 @@
+use macroquad::prelude::*;
+use crate::input::{Action, Bindings};
+use crate::models::{Autopilot, DockId, Fleet, SolarSystem, Spaceship};
+use crate::models::spaceship::SpaceshipState;
+use crate::scene::{Context, Scene, Transition};
+
+// Picking the stops for a ship's trade route
+struct RouteEditor {
+    cursor: usize,
+    stops: Vec<DockId>,
+}
+
+// Overlay listing every ship in the fleet with its status and profit
+pub struct FleetScreen {
+    selected: usize,
+    route_editor: Option<RouteEditor>,
+}
+
+impl FleetScreen {
+    pub fn new(active: usize) -> Self {
+        Self {
+            selected: active,
+            route_editor: None,
+        }
+    }
+
+    fn draw_route_editor(&self, editor: &RouteEditor, fleet: &Fleet, system: &SolarSystem, bindings: &Bindings, x: f32, y: f32) {
+        let ship = &fleet.ships[self.selected];
+        draw_text(&format!("New route for {}", ship.name), x, y, 22.0, WHITE);
+
+        let stops: Vec<&str> = editor.stops.iter().filter_map(|&d| system.dockable(d).map(|b| b.name())).collect();
+        let summary = if stops.is_empty() { "(no stops yet)".to_string() } else { stops.join(" -> ") };
+        draw_text(&summary, x, y + 25.0, 18.0, SKYBLUE);
+
+        // Keep the cursor roughly centred in a scrolling list
+        let rows = ((screen_height() - 340.0) / 22.0).max(1.0) as usize;
+        let docks = system.dock_ids();
+        let first = editor.cursor.saturating_sub(rows / 2).min(docks.len().saturating_sub(rows));
+        for (row, &dock) in docks.iter().enumerate().skip(first).take(rows) {
+            let row_y = y + 60.0 + (row - first) as f32 * 22.0;
+            let Some(body) = system.dockable(dock) else { continue };
+            let produces = body.market().produces.map(|p| p.name()).unwrap_or("Trade hub");
+            let color = if row == editor.cursor { WHITE } else { GRAY };
+            if row == editor.cursor {
+                draw_text(">", x, row_y, 20.0, WHITE);
+            }
+            draw_text(&format!("{} - {}", body.name(), produces), x + 20.0, row_y, 18.0, color);
+        }
+
+        draw_text(
+            &format!(
+                "{}/{} browse  {} add stop  {} remove last  {} confirm (2+ stops)  {} cancel",
+                bindings.label(Action::MenuUp),
+                bindings.label(Action::MenuDown),
+                bindings.label(Action::AddStop),
+                bindings.label(Action::RemoveStop),
+                bindings.label(Action::Confirm),
+                bindings.label(Action::Back),
+            ),
+            x,
+            screen_height() - 100.0,
+            16.0,
+            DARKGRAY,
+        );
+    }
+}
+
+impl Scene for FleetScreen {
+    fn handle_input(&mut self, ctx: &mut Context) -> Transition {
+        let bindings = &ctx.settings.bindings;
+        let Some(game) = ctx.game.as_mut() else { return Transition::Pop };
+        let (fleet, system) = (&mut game.fleet, &game.system);
+        if let Some(editor) = self.route_editor.as_mut() {
+            let docks = system.dock_ids();
+            if bindings.is_pressed(Action::MenuUp) && editor.cursor > 0 {
+                editor.cursor -= 1;
+            }
+            if bindings.is_pressed(Action::MenuDown) && editor.cursor + 1 < docks.len() {
+                editor.cursor += 1;
+            }
+            if bindings.is_pressed(Action::AddStop)
+                && let Some(&dock) = docks.get(editor.cursor)
+                && editor.stops.last() != Some(&dock)
+            {
+                editor.stops.push(dock);
+            }
+            if bindings.is_pressed(Action::RemoveStop) {
+                editor.stops.pop();
+            }
+            if bindings.is_pressed(Action::Confirm) && editor.stops.len() >= 2 {
+                let stops = std::mem::take(&mut editor.stops);
+                fleet.ships[self.selected].autopilot = Some(Autopilot::new(stops));
+                self.route_editor = None;
+            }
+            if bindings.is_pressed(Action::Back) {
+                self.route_editor = None;
+            }
+            return Transition::None;
+        }
+
+        if bindings.is_pressed(Action::MenuUp) && self.selected > 0 {
+            self.selected -= 1;
+        }
+        if bindings.is_pressed(Action::MenuDown) && self.selected + 1 < fleet.ships.len() {
+            self.selected += 1;
+        }
+        if bindings.is_pressed(Action::Confirm) && fleet.switch_to(self.selected) {
+            return Transition::Pop;
+        }
+        if bindings.is_pressed(Action::SetRoute) {
+            self.route_editor = Some(RouteEditor { cursor: 0, stops: Vec::new() });
+        }
+        if bindings.is_pressed(Action::ClearRoute) {
+            fleet.ships[self.selected].autopilot = None;
+        }
+        Transition::pop_if(bindings.is_pressed(Action::Back) || bindings.is_pressed(Action::OpenFleet))
+    }
+
+    fn draw(&mut self, ctx: &mut Context) {
+        let Some(game) = ctx.game.as_ref() else { return };
+        let (fleet, system) = (&game.fleet, &game.system);
+        let ui_x = screen_width() / 2.0 - 380.0;
+        let ui_y = 80.0;
+        let width = 760.0;
+        let height = screen_height() - 160.0;
+        draw_rectangle(ui_x, ui_y, width, height, Color::new(0.0, 0.0, 0.05, 0.9));
+        draw_rectangle_lines(ui_x, ui_y, width, height, 2.0, SKYBLUE);
+        draw_text("FLEET", ui_x + 20.0, ui_y + 35.0, 30.0, YELLOW);
+        draw_text(&format!("Funds: ${}", fleet.purse()), ui_x + 140.0, ui_y + 35.0, 20.0, GOLD);
+
+        if let Some(editor) = &self.route_editor {
+            self.draw_route_editor(editor, fleet, system, &ctx.settings.bindings, ui_x + 20.0, ui_y + 70.0);
+            return;
+        }
+
+        let mut y = ui_y + 70.0;
+        for (i, ship) in fleet.ships.iter().enumerate() {
+            let is_selected = i == self.selected;
+            if is_selected {
+                draw_rectangle(ui_x + 10.0, y - 18.0, width - 20.0, 66.0, Color::new(0.2, 0.3, 0.5, 0.4));
+            }
+
+            let control = if i == fleet.active { " [YOU]" } else { "" };
+            let profit = fleet.profit(ship, &game.ledger, &game.rules.prices);
+            let profit_color = if profit >= 0 { GREEN } else { RED };
+            draw_text(&format!("{}{}", ship.name, control), ui_x + 20.0, y, 22.0, WHITE);
+            draw_text(&ship_status(ship, system), ui_x + 260.0, y, 18.0, LIGHTGRAY);
+            draw_text(&format!("Profit: ${}", profit), ui_x + 600.0, y, 18.0, profit_color);
+
+            let inv = &ship.inventory;
+            draw_text(
+                &format!(
+                    "Fuel {:.0}  Food {}  Hull {:.0}%  Cargo {}/{}",
+                    inv.fuel,
+                    inv.food,
+                    ship.hull_fraction() * 100.0,
+                    inv.total_cargo(),
+                    inv.capacity,
+                ),
+                ui_x + 20.0,
+                y + 20.0,
+                16.0,
+                GRAY,
+            );
+            draw_text(&format!("Route: {}", route_summary(ship, system)), ui_x + 20.0, y + 38.0, 16.0, GRAY);
+            y += 72.0;
+        }
+
+        let bindings = &ctx.settings.bindings;
+        draw_text(
+            &format!(
+                "{}/{} select  {} take control  {} set route  {} clear route  {}/{} close",
+                bindings.label(Action::MenuUp),
+                bindings.label(Action::MenuDown),
+                bindings.label(Action::Confirm),
+                bindings.label(Action::SetRoute),
+                bindings.label(Action::ClearRoute),
+                bindings.label(Action::OpenFleet),
+                bindings.label(Action::Back),
+            ),
+            ui_x + 20.0,
+            ui_y + height - 20.0,
+            18.0,
+            DARKGRAY,
+        );
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
+
+fn dock_name(dock: Option<DockId>, system: &SolarSystem) -> &str {
+    dock.and_then(|d| system.dockable(d)).map(|b| b.name()).unwrap_or("?")
+}
+
+pub fn ship_status(ship: &Spaceship, system: &SolarSystem) -> String {
+    if ship.destroyed {
+        return "Destroyed".to_string();
+    }
+    if ship.inventory.food == 0 {
+        return "Stranded - no food".to_string();
+    }
+    match ship.state {
+        SpaceshipState::Landed => format!("Docked at {}", dock_name(ship.docked_at, system)),
+        SpaceshipState::Landing => format!("Landing at {}", dock_name(ship.docked_at, system)),
+        SpaceshipState::TakingOff => "Launching".to_string(),
+        SpaceshipState::Flying => match ship.autopilot.as_ref().and_then(|a| a.target()) {
+            _ if ship.inventory.fuel <= 0.0 => "Out of fuel".to_string(),
+            Some(target) => format!("En route to {}", dock_name(Some(target), system)),
+            None => "Idle".to_string(),
+        },
+    }
+}
+
+fn route_summary(ship: &Spaceship, system: &SolarSystem) -> String {
+    match &ship.autopilot {
+        Some(autopilot) => autopilot
+            .route
+            .iter()
+            .map(|&d| dock_name(Some(d), system))
+            .collect::<Vec<_>>()
+            .join(" -> "),
+        None => "none".to_string(),
+    }
+}
@@ -0,0 +1,165 @@
+use macroquad::prelude::*;
+use crate::bank::{Bank, BankPenalty};
+use crate::economy::{Inventory, REPUTATION_PER_MISSED_PAYMENT};
+use crate::input::Bindings;
+use crate::ledger::{Ledger, Transaction, TransactionKind};
+use crate::menu::MenuSelection;
+use crate::message_log::MessageLog;
+use crate::models::{BountyHunter, Fleet, SolarSystem, Spaceship, StarType, HUNTER_DAMAGE};
+use crate::models::spaceship::SpaceshipState;
+use crate::rules::GameRules;
+use crate::settings::Settings;
+
+// Everything that makes up one run, from the menu selection to game over.
+// Scenes borrow it through the shared context.
+pub struct Game {
+    pub system: SolarSystem,
+    pub fleet: Fleet,
+    pub ledger: Ledger,
+    pub messages: MessageLog,
+    pub bank: Bank,
+    pub bounty_hunters: Vec<BountyHunter>,
+    pub rules: GameRules, // Balance of this run
+    pub time: f32,        // Seconds of game time, scaled by speed settings
+    pub time_scale: f32,  // One of TIME_SCALES, picked in game
+}
+
+impl Game {
+    pub fn new(selection: &MenuSelection, settings: &Settings) -> Self {
+        let rules = settings.rules_for(selection.difficulty);
+        let seed = (miniquad::date::now() * 1000.0) as u64; // A new system every run
+        let mut game = Self::generate(selection.star_type, selection.planet_count, rules, seed);
+        game.fleet.active_mut().hull_style = settings.hull_style;
+        game
+    }
+
+    // A fresh run in the system built from the seed. Loading a save starts from here too.
+    pub fn generate(star_type: StarType, planet_count: usize, rules: GameRules, seed: u64) -> Self {
+        // Place star at world origin (not screen center)
+        let star_center = Vec2::new(0.0, 0.0);
+        let system = SolarSystem::new(star_center, star_type, planet_count, rules.prices, seed);
+
+        // Create spaceship near the star (starting position in world space)
+        let spaceship_pos = Vec2::new(300.0, 0.0); // Start to the right of the star
+        let flagship = Spaceship::new("Pioneer".to_string(), spaceship_pos, Inventory::new(&rules));
+        let fleet = Fleet::new(flagship);
+
+        Self {
+            system,
+            fleet,
+            ledger: Ledger::new(),
+            messages: MessageLog::new(),
+            bank: Bank::new(rules.prices),
+            bounty_hunters: Vec::new(),
+            rules,
+            time: 0.0,
+            time_scale: 1.0,
+        }
+    }
+
+    // One step of the simulation. The player flies the active ship when
+    // given the bindings; otherwise it holds position.
+    pub fn update(&mut self, dt: f32, player_input: Option<&Bindings>) {
+        // Update solar system
+        self.system.update(dt);
+
+        // Update every ship, the player flies the active one
+        self.fleet.update(dt, &mut self.system, &mut self.ledger, &mut self.messages, &self.rules, player_input);
+
+        // If the ship under control is lost, hand control to another one
+        if !self.fleet.active().is_operational()
+            && let Some(next) = self.fleet.next_operational()
+        {
+            let lost = self.fleet.active().name.clone();
+            self.fleet.switch_to(next);
+            self.messages.error(format!("{} was lost - now commanding {}", lost, self.fleet.active().name));
+        }
+
+        let ship = self.fleet.active_mut();
+        if !ship.destroyed {
+            self.time += dt;
+            self.ledger.set_time(self.time);
+            self.messages.set_time(self.time);
+
+            // Interest and overdue loans
+            let money_before = ship.inventory.money;
+            let cargo_before = ship.inventory.cargo.clone();
+            let penalties = self.bank.update(dt, self.time, &mut ship.inventory);
+
+            // Seizures go in the ledger as the savings and cargo handed to the
+            // bank, the repayment they made and any money handed back
+            let location = ship.docked_at.and_then(|dock| self.system.dockable(dock)).map(|b| b.name()).unwrap_or("Deep space");
+            let mut handed_over = 0;
+            for penalty in &penalties {
+                match penalty {
+                    BankPenalty::SavingsSeized(amount) => {
+                        handed_over += amount;
+                        self.ledger.record(Transaction::new(TransactionKind::Bank, &ship.name, location, *amount));
+                    }
+                    BankPenalty::CargoSeized(_) => {
+                        for (&product, &amount) in &cargo_before {
+                            let value = self.rules.prices.sell_price(product) * amount;
+                            handed_over += value;
+                            self.ledger.record(
+                                Transaction::new(TransactionKind::Bank, &ship.name, location, value).with_product(product, amount),
+                            );
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            let returned = ship.inventory.money - money_before;
+            self.ledger.record(Transaction::new(TransactionKind::Bank, &ship.name, location, returned - handed_over));
+
+            for penalty in &penalties {
+                if matches!(penalty, BankPenalty::BountyHunters) {
+                    self.bounty_hunters.push(BountyHunter::spawn_near(ship.position));
+                    self.bounty_hunters.push(BountyHunter::spawn_near(ship.position));
+                }
+                // Banks share their blacklists, every faction hears about a missed payment
+                if matches!(penalty, BankPenalty::LateFee(_)) {
+                    for faction in &mut self.system.factions {
+                        faction.adjust_reputation(-REPUTATION_PER_MISSED_PAYMENT);
+                    }
+                }
+            }
+            for penalty in &penalties {
+                self.messages.warning(penalty.describe());
+            }
+        }
+
+        // Hunters give up once the debt is settled, and can't touch a landed ship
+        if !self.bank.is_hunting() {
+            self.bounty_hunters.clear();
+        }
+        let exposed = ship.state == SpaceshipState::Flying;
+        for hunter in &mut self.bounty_hunters {
+            if hunter.update(dt, ship.position) && exposed {
+                ship.take_damage(HUNTER_DAMAGE);
+            }
+        }
+    }
+
+    // Camera follows spaceship - center ship on screen
+    pub fn camera_offset(&self) -> Vec2 {
+        let ship = self.fleet.active();
+        Vec2::new(ship.position.x - screen_width() / 2.0, ship.position.y - screen_height() / 2.0)
+    }
+
+    // Out of food or ship destroyed
+    pub fn game_over_reason(&self) -> Option<&'static str> {
+        let ship = self.fleet.active();
+        if ship.destroyed {
+            Some("Ship Destroyed!")
+        } else if ship.inventory.food == 0 {
+            Some("Out of Food!")
+        } else {
+            None
+        }
+    }
+
+    // The active ship is sitting at a dock
+    pub fn is_docked(&self) -> bool {
+        self.fleet.active().state == SpaceshipState::Landed
+    }
+}
@@ -0,0 +1,62 @@
+use macroquad::prelude::*;
+use crate::input::Action;
+use crate::menu::Menu;
+use crate::scene::{Context, Scene, Transition};
+use crate::settings_screen::SettingsScreen;
+
+// Shown over the frozen flight view once the run is lost. A finished run has
+// nothing to pause, so back goes straight to the menu.
+pub struct GameOverScreen {
+    reason: &'static str,
+}
+
+impl GameOverScreen {
+    pub fn new(reason: &'static str) -> Self {
+        Self { reason }
+    }
+}
+
+impl Scene for GameOverScreen {
+    fn handle_input(&mut self, ctx: &mut Context) -> Transition {
+        let bindings = &ctx.settings.bindings;
+        if bindings.is_pressed(Action::Back) {
+            ctx.game = None;
+            Transition::Reset(Box::new(Menu::new()))
+        } else if bindings.is_pressed(Action::OpenSettings) {
+            Transition::Push(Box::new(SettingsScreen::new()))
+        } else {
+            Transition::None
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context) {
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.7));
+        let game_over_text = &format!("GAME OVER - {}", self.reason);
+        let text_size = 40.0;
+        let text_dims = measure_text(game_over_text, None, text_size as u16, 1.0);
+        draw_text(
+            game_over_text,
+            screen_width() / 2.0 - text_dims.width / 2.0,
+            screen_height() / 2.0,
+            text_size,
+            RED,
+        );
+        let restart_text = format!("Press {} to return to menu", ctx.settings.bindings.label(Action::Back));
+        let restart_dims = measure_text(&restart_text, None, 20, 1.0);
+        draw_text(
+            &restart_text,
+            screen_width() / 2.0 - restart_dims.width / 2.0,
+            screen_height() / 2.0 + 50.0,
+            20.0,
+            WHITE,
+        );
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+
+    fn freezes_below(&self) -> bool {
+        true
+    }
+}
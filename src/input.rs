@@ -0,0 +1,510 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use gilrs::{Axis, Button, EventType, Gilrs};
+use macroquad::prelude::*;
+
+// Where key bindings are kept, relative to the working directory
+pub const BINDINGS_PATH: &str = "bindings.cfg";
+
+const STICK_DEADZONE: f32 = 0.5; // How far the left stick is pushed before it counts as the d-pad
+
+// Every key that can be bound, so bindings can be written out and read back by name
+const BINDABLE_KEYS: [KeyCode; 73] = [
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G, KeyCode::H,
+    KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N, KeyCode::O, KeyCode::P,
+    KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U, KeyCode::V, KeyCode::W, KeyCode::X,
+    KeyCode::Y, KeyCode::Z,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4, KeyCode::Key5,
+    KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    KeyCode::Kp0, KeyCode::Kp1, KeyCode::Kp2, KeyCode::Kp3, KeyCode::Kp4, KeyCode::Kp5,
+    KeyCode::Kp6, KeyCode::Kp7, KeyCode::Kp8, KeyCode::Kp9,
+    KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right,
+    KeyCode::Space, KeyCode::Enter, KeyCode::Escape, KeyCode::Tab, KeyCode::Backspace, KeyCode::Delete,
+    KeyCode::LeftShift, KeyCode::RightShift, KeyCode::LeftControl, KeyCode::RightControl,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6, KeyCode::F7,
+    KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12, KeyCode::KpEnter,
+];
+
+// Where an action is used. Actions in the same context can't share a key
+// without clashing; Global actions clash with everything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Context {
+    Flight,
+    Docked,
+    Global,
+    Menu,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    ThrustUp,
+    ThrustDown,
+    ThrustLeft,
+    ThrustRight,
+    Mine,
+    Land,
+    TakeOff,
+    BuyProduct,
+    NextProduct,
+    BuyFuel,
+    BuyFood,
+    BuyLaser,
+    RepairHull,
+    BuyInsurance,
+    BuyShip,
+    BuyCompartment,
+    MarketInfo,
+    Borrow,
+    Repay,
+    Deposit,
+    Withdraw,
+    BuildFirstFactory,
+    BuildSecondFactory,
+    CollectFactories,
+    OpenFleet,
+    OpenLedger,
+    OpenMessages,
+    OpenSettings,
+    SlowerTime,
+    FasterTime,
+    Back,
+    MenuUp,
+    MenuDown,
+    MenuLeft,
+    MenuRight,
+    Confirm,
+    NextTab,
+    ExportLedger,
+    SetRoute,
+    ClearRoute,
+    AddStop,
+    RemoveStop,
+    ResetBinding,
+    LoadGame,
+}
+
+impl Action {
+    pub fn all() -> Vec<Action> {
+        vec![
+            Action::ThrustUp,
+            Action::ThrustDown,
+            Action::ThrustLeft,
+            Action::ThrustRight,
+            Action::Mine,
+            Action::Land,
+            Action::TakeOff,
+            Action::BuyProduct,
+            Action::NextProduct,
+            Action::BuyFuel,
+            Action::BuyFood,
+            Action::BuyLaser,
+            Action::RepairHull,
+            Action::BuyInsurance,
+            Action::BuyShip,
+            Action::BuyCompartment,
+            Action::MarketInfo,
+            Action::Borrow,
+            Action::Repay,
+            Action::Deposit,
+            Action::Withdraw,
+            Action::BuildFirstFactory,
+            Action::BuildSecondFactory,
+            Action::CollectFactories,
+            Action::OpenFleet,
+            Action::OpenLedger,
+            Action::OpenMessages,
+            Action::OpenSettings,
+            Action::SlowerTime,
+            Action::FasterTime,
+            Action::Back,
+            Action::MenuUp,
+            Action::MenuDown,
+            Action::MenuLeft,
+            Action::MenuRight,
+            Action::Confirm,
+            Action::NextTab,
+            Action::ExportLedger,
+            Action::SetRoute,
+            Action::ClearRoute,
+            Action::AddStop,
+            Action::RemoveStop,
+            Action::ResetBinding,
+            Action::LoadGame,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::ThrustUp => "Thrust up",
+            Action::ThrustDown => "Thrust down",
+            Action::ThrustLeft => "Thrust left",
+            Action::ThrustRight => "Thrust right",
+            Action::Mine => "Fire mining laser",
+            Action::Land => "Land",
+            Action::TakeOff => "Take off",
+            Action::BuyProduct => "Buy local product",
+            Action::NextProduct => "Next product",
+            Action::BuyFuel => "Buy fuel",
+            Action::BuyFood => "Buy food",
+            Action::BuyLaser => "Buy mining laser",
+            Action::RepairHull => "Repair hull",
+            Action::BuyInsurance => "Buy insurance",
+            Action::BuyShip => "Buy ship",
+            Action::BuyCompartment => "Buy smuggler's compartment",
+            Action::MarketInfo => "Market info",
+            Action::Borrow => "Borrow",
+            Action::Repay => "Repay loan",
+            Action::Deposit => "Deposit",
+            Action::Withdraw => "Withdraw",
+            Action::BuildFirstFactory => "Build first factory type",
+            Action::BuildSecondFactory => "Build second factory type",
+            Action::CollectFactories => "Collect factory earnings",
+            Action::OpenFleet => "Fleet screen",
+            Action::OpenLedger => "Ledger",
+            Action::OpenMessages => "Message log",
+            Action::OpenSettings => "Settings",
+            Action::SlowerTime => "Slow down time",
+            Action::FasterTime => "Speed up time",
+            Action::Back => "Back / close",
+            Action::MenuUp => "Menu up",
+            Action::MenuDown => "Menu down",
+            Action::MenuLeft => "Menu left",
+            Action::MenuRight => "Menu right",
+            Action::Confirm => "Menu select",
+            Action::NextTab => "Next tab / filter",
+            Action::ExportLedger => "Export ledger",
+            Action::SetRoute => "Set ship route",
+            Action::ClearRoute => "Clear ship route",
+            Action::AddStop => "Add route stop",
+            Action::RemoveStop => "Remove route stop",
+            Action::ResetBinding => "Reset binding to default",
+            Action::LoadGame => "Load saved game",
+        }
+    }
+
+    // Name used in the bindings file. Kept apart from the display name so
+    // relabelling an action doesn't orphan saved bindings.
+    fn id(&self) -> &'static str {
+        match self {
+            Action::ThrustUp => "thrust_up",
+            Action::ThrustDown => "thrust_down",
+            Action::ThrustLeft => "thrust_left",
+            Action::ThrustRight => "thrust_right",
+            Action::Mine => "mine",
+            Action::Land => "land",
+            Action::TakeOff => "take_off",
+            Action::BuyProduct => "buy_product",
+            Action::NextProduct => "next_product",
+            Action::BuyFuel => "buy_fuel",
+            Action::BuyFood => "buy_food",
+            Action::BuyLaser => "buy_laser",
+            Action::RepairHull => "repair_hull",
+            Action::BuyInsurance => "buy_insurance",
+            Action::BuyShip => "buy_ship",
+            Action::BuyCompartment => "buy_compartment",
+            Action::MarketInfo => "market_info",
+            Action::Borrow => "borrow",
+            Action::Repay => "repay",
+            Action::Deposit => "deposit",
+            Action::Withdraw => "withdraw",
+            Action::BuildFirstFactory => "build_first_factory",
+            Action::BuildSecondFactory => "build_second_factory",
+            Action::CollectFactories => "collect_factories",
+            Action::OpenFleet => "open_fleet",
+            Action::OpenLedger => "open_ledger",
+            Action::OpenMessages => "open_messages",
+            Action::OpenSettings => "open_settings",
+            Action::SlowerTime => "slower_time",
+            Action::FasterTime => "faster_time",
+            Action::Back => "back",
+            Action::MenuUp => "menu_up",
+            Action::MenuDown => "menu_down",
+            Action::MenuLeft => "menu_left",
+            Action::MenuRight => "menu_right",
+            Action::Confirm => "confirm",
+            Action::NextTab => "next_tab",
+            Action::ExportLedger => "export_ledger",
+            Action::SetRoute => "set_route",
+            Action::ClearRoute => "clear_route",
+            Action::AddStop => "add_stop",
+            Action::RemoveStop => "remove_stop",
+            Action::ResetBinding => "reset_binding",
+            Action::LoadGame => "load_game",
+        }
+    }
+
+    pub fn context(&self) -> Context {
+        match self {
+            Action::ThrustUp
+            | Action::ThrustDown
+            | Action::ThrustLeft
+            | Action::ThrustRight
+            | Action::Mine
+            | Action::Land => Context::Flight,
+            Action::OpenFleet
+            | Action::OpenLedger
+            | Action::OpenMessages
+            | Action::OpenSettings
+            | Action::SlowerTime
+            | Action::FasterTime
+            | Action::Back => Context::Global,
+            Action::MenuUp
+            | Action::MenuDown
+            | Action::MenuLeft
+            | Action::MenuRight
+            | Action::Confirm
+            | Action::NextTab
+            | Action::ExportLedger
+            | Action::SetRoute
+            | Action::ClearRoute
+            | Action::AddStop
+            | Action::RemoveStop
+            | Action::ResetBinding
+            | Action::LoadGame => Context::Menu,
+            _ => Context::Docked,
+        }
+    }
+
+    fn default_keys(&self) -> Vec<KeyCode> {
+        match self {
+            Action::ThrustUp => vec![KeyCode::W, KeyCode::Up],
+            Action::ThrustDown => vec![KeyCode::S, KeyCode::Down],
+            Action::ThrustLeft => vec![KeyCode::A, KeyCode::Left],
+            Action::ThrustRight => vec![KeyCode::D, KeyCode::Right],
+            Action::Mine => vec![KeyCode::M],
+            Action::Land => vec![KeyCode::Space],
+            Action::TakeOff => vec![KeyCode::Space],
+            Action::BuyProduct => vec![KeyCode::Key1, KeyCode::Kp1],
+            Action::NextProduct => vec![KeyCode::Tab],
+            Action::BuyFuel => vec![KeyCode::Key2, KeyCode::Kp2],
+            Action::BuyFood => vec![KeyCode::Key3, KeyCode::Kp3],
+            Action::BuyLaser => vec![KeyCode::Key4, KeyCode::Kp4],
+            Action::RepairHull => vec![KeyCode::Key5, KeyCode::Kp5],
+            Action::BuyInsurance => vec![KeyCode::Key6, KeyCode::Kp6],
+            Action::BuyShip => vec![KeyCode::Key7, KeyCode::Kp7],
+            Action::BuyCompartment => vec![KeyCode::Key8, KeyCode::Kp8],
+            Action::MarketInfo => vec![KeyCode::I],
+            Action::Borrow => vec![KeyCode::B],
+            Action::Repay => vec![KeyCode::R],
+            Action::Deposit => vec![KeyCode::G],
+            Action::Withdraw => vec![KeyCode::H],
+            Action::BuildFirstFactory => vec![KeyCode::J],
+            Action::BuildSecondFactory => vec![KeyCode::K],
+            Action::CollectFactories => vec![KeyCode::L],
+            Action::OpenFleet => vec![KeyCode::F],
+            Action::OpenLedger => vec![KeyCode::T],
+            Action::OpenMessages => vec![KeyCode::N],
+            Action::OpenSettings => vec![KeyCode::O],
+            Action::SlowerTime => vec![KeyCode::Z],
+            Action::FasterTime => vec![KeyCode::X],
+            Action::Back => vec![KeyCode::Escape],
+            Action::MenuUp => vec![KeyCode::Up],
+            Action::MenuDown => vec![KeyCode::Down],
+            Action::MenuLeft => vec![KeyCode::Left],
+            Action::MenuRight => vec![KeyCode::Right],
+            Action::Confirm => vec![KeyCode::Enter, KeyCode::KpEnter, KeyCode::Space],
+            Action::NextTab => vec![KeyCode::Tab],
+            Action::ExportLedger => vec![KeyCode::E],
+            Action::SetRoute => vec![KeyCode::R],
+            Action::ClearRoute => vec![KeyCode::C],
+            Action::AddStop => vec![KeyCode::A],
+            Action::RemoveStop => vec![KeyCode::Backspace],
+            Action::ResetBinding => vec![KeyCode::Delete],
+            Action::LoadGame => vec![KeyCode::L],
+        }
+    }
+
+    // Gamepad buttons aren't rebindable; the docked purchases stay on the keyboard
+    fn default_buttons(&self) -> Vec<Button> {
+        match self {
+            Action::ThrustUp | Action::MenuUp => vec![Button::DPadUp],
+            Action::ThrustDown | Action::MenuDown => vec![Button::DPadDown],
+            Action::ThrustLeft | Action::MenuLeft => vec![Button::DPadLeft],
+            Action::ThrustRight | Action::MenuRight => vec![Button::DPadRight],
+            Action::Mine => vec![Button::RightTrigger2],
+            Action::Land | Action::TakeOff | Action::Confirm => vec![Button::South],
+            Action::BuyProduct | Action::AddStop => vec![Button::West],
+            Action::NextProduct | Action::NextTab | Action::RemoveStop => vec![Button::North],
+            Action::OpenFleet => vec![Button::Select],
+            Action::SlowerTime => vec![Button::LeftTrigger],
+            Action::FasterTime => vec![Button::RightTrigger],
+            Action::Back => vec![Button::East, Button::Start],
+            _ => Vec::new(),
+        }
+    }
+}
+
+// Every connected gamepad, read through gilrs. Held buttons come from gilrs'
+// own state; presses are collected from its events once a frame by poll.
+struct Gamepads {
+    gilrs: Option<Gilrs>, // None where gilrs can't reach the platform's gamepads
+    pressed: Vec<Button>,
+}
+
+impl Gamepads {
+    fn new() -> Self {
+        Self { gilrs: Gilrs::new().ok(), pressed: Vec::new() }
+    }
+
+    fn poll(&mut self) {
+        self.pressed.clear();
+        let Some(gilrs) = self.gilrs.as_mut() else { return };
+        while let Some(event) = gilrs.next_event() {
+            if let EventType::ButtonPressed(button, _) = event.event {
+                self.pressed.push(button);
+            }
+        }
+    }
+
+    // The left stick works as the d-pad
+    fn is_down(&self, button: Button) -> bool {
+        let Some(gilrs) = &self.gilrs else { return false };
+        let stick = match button {
+            Button::DPadUp => Some((Axis::LeftStickY, 1.0)),
+            Button::DPadDown => Some((Axis::LeftStickY, -1.0)),
+            Button::DPadLeft => Some((Axis::LeftStickX, -1.0)),
+            Button::DPadRight => Some((Axis::LeftStickX, 1.0)),
+            _ => None,
+        };
+        gilrs.gamepads().any(|(_, pad)| {
+            pad.is_pressed(button) || stick.is_some_and(|(axis, sign)| pad.value(axis) * sign > STICK_DEADZONE)
+        })
+    }
+
+    fn is_pressed(&self, button: Button) -> bool {
+        self.pressed.contains(&button)
+    }
+}
+
+// Which keys and gamepad buttons trigger which actions. Game code asks about
+// actions, never keys.
+pub struct Bindings {
+    keys: HashMap<Action, Vec<KeyCode>>,
+    gamepads: Gamepads,
+}
+
+impl Bindings {
+    pub fn new() -> Self {
+        Self {
+            keys: Action::all().into_iter().map(|action| (action, action.default_keys())).collect(),
+            gamepads: Gamepads::new(),
+        }
+    }
+
+    // Call once at the start of every frame, before anything asks about input
+    pub fn poll_gamepads(&mut self) {
+        self.gamepads.poll();
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.keys.get(&action).map(|keys| keys.as_slice()).unwrap_or(&[])
+    }
+
+    pub fn is_down(&self, action: Action) -> bool {
+        self.keys(action).iter().any(|&key| is_key_down(key))
+            || action.default_buttons().into_iter().any(|button| self.gamepads.is_down(button))
+    }
+
+    pub fn is_pressed(&self, action: Action) -> bool {
+        self.keys(action).iter().any(|&key| is_key_pressed(key))
+            || action.default_buttons().into_iter().any(|button| self.gamepads.is_pressed(button))
+    }
+
+    // The first key, for prompts like "[2] Buy Fuel"
+    pub fn label(&self, action: Action) -> String {
+        self.keys(action).first().map(|&key| key_name(key)).unwrap_or_else(|| "-".to_string())
+    }
+
+    // Every key and gamepad button, for the controls screen
+    pub fn describe(&self, action: Action) -> String {
+        let mut names: Vec<String> = self.keys(action).iter().map(|&key| key_name(key)).collect();
+        names.extend(action.default_buttons().into_iter().map(|button| format!("pad {}", button_name(button))));
+        if names.is_empty() { "unbound".to_string() } else { names.join(" / ") }
+    }
+
+    // Rebinding replaces all of an action's keys with the new one
+    pub fn bind(&mut self, action: Action, key: KeyCode) {
+        self.keys.insert(action, vec![key]);
+    }
+
+    pub fn reset(&mut self, action: Action) {
+        self.keys.insert(action, action.default_keys());
+    }
+
+    // Other actions that would fire alongside this one
+    pub fn conflicts(&self, action: Action) -> Vec<Action> {
+        let clashes = |other: Action| {
+            let (a, b) = (action.context(), other.context());
+            a == b || (a == Context::Global && b != Context::Menu) || (b == Context::Global && a != Context::Menu)
+        };
+        Action::all()
+            .into_iter()
+            .filter(|&other| other != action && clashes(other))
+            .filter(|&other| self.keys(other).iter().any(|key| self.keys(action).contains(key)))
+            .collect()
+    }
+
+    // Falls back to the defaults for a missing file and for any action
+    // whose line doesn't parse
+    pub fn load(path: &str) -> Self {
+        let mut bindings = Bindings::new();
+        let Ok(contents) = fs::read_to_string(path) else { return bindings };
+        for line in contents.lines() {
+            let Some((id, value)) = line.split_once('=') else { continue };
+            let Some(action) = Action::all().into_iter().find(|action| action.id() == id.trim()) else { continue };
+            let keys: Vec<KeyCode> = value.split(',').filter_map(|name| parse_key(name.trim())).collect();
+            if !keys.is_empty() {
+                bindings.keys.insert(action, keys);
+            }
+        }
+        bindings
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut config = String::from("# Solar Economy key bindings\n");
+        for action in Action::all() {
+            let names: Vec<String> = self.keys(action).iter().map(|&key| format!("{:?}", key)).collect();
+            config.push_str(&format!("{} = {}\n", action.id(), names.join(", ")));
+        }
+        fs::write(path, config)
+    }
+}
+
+pub fn is_bindable(key: KeyCode) -> bool {
+    BINDABLE_KEYS.contains(&key)
+}
+
+// "Key1" reads better as "1"
+pub fn key_name(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    match name.strip_prefix("Key") {
+        Some(digit) => digit.to_string(),
+        None => name,
+    }
+}
+
+fn parse_key(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS.into_iter().find(|key| format!("{:?}", key) == name)
+}
+
+// Named after the Xbox layout, which most pads copy
+fn button_name(button: Button) -> &'static str {
+    match button {
+        Button::South => "A",
+        Button::East => "B",
+        Button::West => "X",
+        Button::North => "Y",
+        Button::LeftTrigger => "LB",
+        Button::RightTrigger => "RB",
+        Button::LeftTrigger2 => "LT",
+        Button::RightTrigger2 => "RT",
+        Button::Select => "Back",
+        Button::Start => "Start",
+        Button::DPadUp => "Up",
+        Button::DPadDown => "Down",
+        Button::DPadLeft => "Left",
+        Button::DPadRight => "Right",
+        _ => "?",
+    }
+}
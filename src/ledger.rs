@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use crate::economy::{ProductType, TradeQuote, TradeSide};
+
+// Where ledger exports are written, relative to the working directory
+pub const LEDGER_EXPORT_PATH: &str = "ledger.csv";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionKind {
+    Purchase,
+    Sale,
+    Fuel,
+    Food,
+    Equipment,
+    Repair,
+    Insurance,
+    Ship,
+    Factory,
+    Bank,
+    Tariff,
+    Tax,
+    DockingFee,
+    Fine,
+}
+
+impl TransactionKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TransactionKind::Purchase => "Purchase",
+            TransactionKind::Sale => "Sale",
+            TransactionKind::Fuel => "Fuel",
+            TransactionKind::Food => "Food",
+            TransactionKind::Equipment => "Equipment",
+            TransactionKind::Repair => "Repair",
+            TransactionKind::Insurance => "Insurance",
+            TransactionKind::Ship => "Ship",
+            TransactionKind::Factory => "Factory",
+            TransactionKind::Bank => "Bank",
+            TransactionKind::Tariff => "Tariff",
+            TransactionKind::Tax => "Sales tax",
+            TransactionKind::DockingFee => "Docking fee",
+            TransactionKind::Fine => "Fine",
+        }
+    }
+
+    // Who is on the other side of this kind of deal
+    pub fn counterparty(&self) -> &'static str {
+        match self {
+            TransactionKind::Purchase | TransactionKind::Sale => "Market",
+            TransactionKind::Fuel | TransactionKind::Food => "Supply depot",
+            TransactionKind::Equipment => "Outfitter",
+            TransactionKind::Repair | TransactionKind::Ship => "Shipyard",
+            TransactionKind::Insurance => "Insurer",
+            TransactionKind::Factory => "Factory",
+            TransactionKind::Bank => "Planetary bank",
+            TransactionKind::Tariff | TransactionKind::Fine => "Customs",
+            TransactionKind::Tax => "Tax office",
+            TransactionKind::DockingFee => "Port authority",
+        }
+    }
+}
+
+// One movement of money. `amount` is positive when the player was paid
+#[derive(Debug, Clone)]
+pub struct Transaction {
+    pub time: f32,
+    pub kind: TransactionKind,
+    pub ship: String,
+    pub location: String,
+    pub product: Option<ProductType>,
+    pub quantity: i32,
+    pub amount: i32,
+}
+
+impl Transaction {
+    pub fn new(kind: TransactionKind, ship: &str, location: &str, amount: i32) -> Self {
+        Self {
+            time: 0.0,
+            kind,
+            ship: ship.to_string(),
+            location: location.to_string(),
+            product: None,
+            quantity: 0,
+            amount,
+        }
+    }
+
+    pub fn with_product(mut self, product: ProductType, quantity: i32) -> Self {
+        self.product = Some(product);
+        self.quantity = quantity;
+        self
+    }
+}
+
+// A line in a profit and loss report
+#[derive(Debug, Clone)]
+pub struct PnlRow {
+    pub label: String,
+    pub units: i32,
+    pub cost: i32,
+    pub revenue: i32,
+}
+
+impl PnlRow {
+    fn new(label: String) -> Self {
+        Self { label, units: 0, cost: 0, revenue: 0 }
+    }
+
+    pub fn profit(&self) -> i32 {
+        self.revenue - self.cost
+    }
+}
+
+// What a ship paid for the goods still in its hold, and where it got them
+#[derive(Default)]
+struct CostBasis {
+    units: i32,
+    cost: i32,
+    origin: Option<String>,
+}
+
+// Every transaction made by any of the player's ships
+pub struct Ledger {
+    pub entries: Vec<Transaction>,
+    now: f32,
+}
+
+impl Ledger {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            now: 0.0,
+        }
+    }
+
+    // Game time stamped on everything recorded from now on
+    pub fn set_time(&mut self, now: f32) {
+        self.now = now;
+    }
+
+    pub fn record(&mut self, mut transaction: Transaction) {
+        if transaction.amount == 0 {
+            return;
+        }
+        transaction.time = self.now;
+        self.entries.push(transaction);
+    }
+
+    // A trade becomes separate lines for the goods, the tariff and the sales tax
+    pub fn record_trade(&mut self, ship: &str, location: &str, quote: &TradeQuote) {
+        let goods = match quote.side {
+            TradeSide::Buy => Transaction::new(TransactionKind::Purchase, ship, location, -quote.goods),
+            TradeSide::Sell => Transaction::new(TransactionKind::Sale, ship, location, quote.goods),
+        };
+        self.record(goods.with_product(quote.product, quote.quantity));
+        self.record(
+            Transaction::new(TransactionKind::Tariff, ship, location, -quote.tariff).with_product(quote.product, quote.quantity),
+        );
+        self.record(
+            Transaction::new(TransactionKind::Tax, ship, location, -quote.tax).with_product(quote.product, quote.quantity),
+        );
+    }
+
+    // What one ship has made or lost. Bank loans and savings only move money
+    // around, so they're left out.
+    pub fn earnings(&self, ship: &str) -> i32 {
+        self.entries
+            .iter()
+            .filter(|entry| entry.ship == ship && entry.kind != TransactionKind::Bank)
+            .map(|entry| entry.amount)
+            .sum()
+    }
+
+    pub fn income(&self) -> i32 {
+        self.entries.iter().map(|t| t.amount.max(0)).sum()
+    }
+
+    pub fn expenses(&self) -> i32 {
+        self.entries.iter().map(|t| (-t.amount).max(0)).sum()
+    }
+
+    // Money spent buying and earned selling each product
+    pub fn product_report(&self) -> Vec<PnlRow> {
+        let mut rows: Vec<PnlRow> = Vec::new();
+        for product in ProductType::all() {
+            let mut row = PnlRow::new(product.name().to_string());
+            for t in self.entries.iter().filter(|t| t.product == Some(product)) {
+                match t.kind {
+                    TransactionKind::Purchase => row.cost -= t.amount,
+                    TransactionKind::Sale => {
+                        row.revenue += t.amount;
+                        row.units += t.quantity;
+                    }
+                    _ => {}
+                }
+            }
+            if row.cost != 0 || row.revenue != 0 {
+                rows.push(row);
+            }
+        }
+        rows
+    }
+
+    // Sales grouped by where the goods were bought and where they were sold.
+    // Each sale is charged the average price the ship paid for that product;
+    // mined cargo costs nothing.
+    pub fn route_report(&self) -> Vec<PnlRow> {
+        let mut holds: HashMap<(&str, ProductType), CostBasis> = HashMap::new();
+        let mut rows: Vec<PnlRow> = Vec::new();
+
+        for t in &self.entries {
+            let Some(product) = t.product else { continue };
+            let basis = holds.entry((t.ship.as_str(), product)).or_default();
+            match t.kind {
+                TransactionKind::Purchase => {
+                    basis.units += t.quantity;
+                    basis.cost -= t.amount;
+                    basis.origin = Some(t.location.clone());
+                }
+                TransactionKind::Sale => {
+                    let covered = t.quantity.min(basis.units);
+                    let cost = if basis.units > 0 { basis.cost * covered / basis.units } else { 0 };
+                    basis.units -= covered;
+                    basis.cost -= cost;
+
+                    let origin = basis.origin.as_deref().filter(|_| covered > 0).unwrap_or("Mined");
+                    let label = format!("{} -> {}", origin, t.location);
+                    let index = match rows.iter().position(|r| r.label == label) {
+                        Some(index) => index,
+                        None => {
+                            rows.push(PnlRow::new(label));
+                            rows.len() - 1
+                        }
+                    };
+                    rows[index].units += t.quantity;
+                    rows[index].cost += cost;
+                    rows[index].revenue += t.amount;
+                }
+                _ => {}
+            }
+        }
+
+        rows.sort_by_key(|row| std::cmp::Reverse(row.profit()));
+        rows
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("time,ship,location,counterparty,kind,product,quantity,amount\n");
+        for t in &self.entries {
+            let product = t.product.map(|p| p.name()).unwrap_or("");
+            csv.push_str(&format!(
+                "{:.1},{},{},{},{},{},{},{}\n",
+                t.time,
+                csv_field(&t.ship),
+                csv_field(&t.location),
+                csv_field(t.kind.counterparty()),
+                t.kind.name(),
+                product,
+                t.quantity,
+                t.amount,
+            ));
+        }
+        csv
+    }
+
+    pub fn export_csv(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_csv())
+    }
+}
+
+// Quote a value if it would otherwise break the row apart
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
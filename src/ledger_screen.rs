@@ -0,0 +1,174 @@
+use macroquad::prelude::*;
+use crate::input::Action;
+use crate::ledger::{Ledger, PnlRow, LEDGER_EXPORT_PATH};
+use crate::scene::{Context, Scene, Transition};
+
+#[derive(Clone, Copy, PartialEq)]
+enum LedgerTab {
+    Transactions,
+    Products,
+    Routes,
+}
+
+impl LedgerTab {
+    fn name(&self) -> &'static str {
+        match self {
+            LedgerTab::Transactions => "Transactions",
+            LedgerTab::Products => "By product",
+            LedgerTab::Routes => "By route",
+        }
+    }
+
+    fn next(&self) -> LedgerTab {
+        match self {
+            LedgerTab::Transactions => LedgerTab::Products,
+            LedgerTab::Products => LedgerTab::Routes,
+            LedgerTab::Routes => LedgerTab::Transactions,
+        }
+    }
+}
+
+// Overlay for browsing the ledger and its profit and loss reports
+pub struct LedgerScreen {
+    tab: LedgerTab,
+    scroll: usize,
+    status: Option<String>,
+}
+
+impl LedgerScreen {
+    pub fn new() -> Self {
+        Self {
+            tab: LedgerTab::Transactions,
+            scroll: 0,
+            status: None,
+        }
+    }
+
+    // Newest first, so the top of the list is what just happened
+    fn draw_transactions(&self, ledger: &Ledger, x: f32, y: f32, rows: usize) {
+        draw_text("Time    Ship          Location              Kind        Product        Qty    Amount", x, y, 16.0, GRAY);
+        if ledger.entries.is_empty() {
+            draw_text("No transactions yet", x, y + 25.0, 18.0, GRAY);
+            return;
+        }
+
+        let first = self.scroll.min(ledger.entries.len().saturating_sub(1));
+        for (row, t) in ledger.entries.iter().rev().skip(first).take(rows).enumerate() {
+            let row_y = y + 22.0 + row as f32 * 22.0;
+            let product = t.product.map(|p| p.name()).unwrap_or("-");
+            let quantity = if t.quantity > 0 { t.quantity.to_string() } else { "-".to_string() };
+            draw_text(&format!("{:>5.0}s", t.time), x, row_y, 16.0, LIGHTGRAY);
+            draw_text(&t.ship, x + 56.0, row_y, 16.0, LIGHTGRAY);
+            draw_text(&t.location, x + 160.0, row_y, 16.0, LIGHTGRAY);
+            draw_text(t.kind.name(), x + 330.0, row_y, 16.0, LIGHTGRAY);
+            draw_text(product, x + 425.0, row_y, 16.0, LIGHTGRAY);
+            draw_text(&quantity, x + 545.0, row_y, 16.0, LIGHTGRAY);
+            draw_text(&format!("{:+}", t.amount), x + 600.0, row_y, 16.0, if t.amount >= 0 { GREEN } else { RED });
+        }
+    }
+
+    fn draw_report(&self, report: &[PnlRow], heading: &str, x: f32, y: f32, rows: usize) {
+        draw_text(heading, x, y, 16.0, GRAY);
+        draw_text("Sold      Cost   Revenue    Profit", x + 420.0, y, 16.0, GRAY);
+        if report.is_empty() {
+            draw_text("Nothing bought or sold yet", x, y + 25.0, 18.0, GRAY);
+            return;
+        }
+
+        let first = self.scroll.min(report.len().saturating_sub(1));
+        for (row, line) in report.iter().skip(first).take(rows).enumerate() {
+            let row_y = y + 22.0 + row as f32 * 22.0;
+            let profit = line.profit();
+            draw_text(&line.label, x, row_y, 16.0, LIGHTGRAY);
+            draw_text(
+                &format!("{:>4}  {:>8}  {:>8}", line.units, line.cost, line.revenue),
+                x + 420.0,
+                row_y,
+                16.0,
+                LIGHTGRAY,
+            );
+            draw_text(&format!("{:>8}", profit), x + 640.0, row_y, 16.0, if profit >= 0 { GREEN } else { RED });
+        }
+    }
+}
+
+impl Scene for LedgerScreen {
+    fn handle_input(&mut self, ctx: &mut Context) -> Transition {
+        let bindings = &ctx.settings.bindings;
+        let Some(game) = ctx.game.as_ref() else { return Transition::Pop };
+        let ledger = &game.ledger;
+        if bindings.is_pressed(Action::NextTab) {
+            self.tab = self.tab.next();
+            self.scroll = 0;
+        }
+        if bindings.is_pressed(Action::MenuUp) {
+            self.scroll = self.scroll.saturating_sub(1);
+        }
+        if bindings.is_pressed(Action::MenuDown) {
+            self.scroll += 1;
+        }
+        if bindings.is_pressed(Action::ExportLedger) {
+            self.status = Some(match ledger.export_csv(LEDGER_EXPORT_PATH) {
+                Ok(()) => format!("Exported {} transactions to {}", ledger.entries.len(), LEDGER_EXPORT_PATH),
+                Err(err) => format!("Export failed: {}", err),
+            });
+        }
+        Transition::pop_if(bindings.is_pressed(Action::Back) || bindings.is_pressed(Action::OpenLedger))
+    }
+
+    fn draw(&mut self, ctx: &mut Context) {
+        let Some(game) = ctx.game.as_ref() else { return };
+        let ledger = &game.ledger;
+        let ui_x = screen_width() / 2.0 - 380.0;
+        let ui_y = 80.0;
+        let width = 760.0;
+        let height = screen_height() - 160.0;
+        draw_rectangle(ui_x, ui_y, width, height, Color::new(0.0, 0.0, 0.05, 0.9));
+        draw_rectangle_lines(ui_x, ui_y, width, height, 2.0, SKYBLUE);
+        draw_text("LEDGER", ui_x + 20.0, ui_y + 35.0, 30.0, YELLOW);
+        draw_text(self.tab.name(), ui_x + 160.0, ui_y + 35.0, 22.0, WHITE);
+
+        let income = ledger.income();
+        let expenses = ledger.expenses();
+        let net = income - expenses;
+        draw_text(
+            &format!("Income ${}  Expenses ${}  Net ${}", income, expenses, net),
+            ui_x + 380.0,
+            ui_y + 35.0,
+            18.0,
+            if net >= 0 { GREEN } else { RED },
+        );
+
+        let rows = ((height - 140.0) / 22.0).max(1.0) as usize;
+        let x = ui_x + 20.0;
+        let y = ui_y + 75.0;
+        match self.tab {
+            LedgerTab::Transactions => self.draw_transactions(ledger, x, y, rows),
+            LedgerTab::Products => self.draw_report(&ledger.product_report(), "Product", x, y, rows),
+            LedgerTab::Routes => self.draw_report(&ledger.route_report(), "Route", x, y, rows),
+        }
+
+        if let Some(status) = &self.status {
+            draw_text(status, x, ui_y + height - 45.0, 18.0, SKYBLUE);
+        }
+        let bindings = &ctx.settings.bindings;
+        draw_text(
+            &format!(
+                "{} switch report  {}/{} scroll  {} export CSV  {} close",
+                bindings.label(Action::NextTab),
+                bindings.label(Action::MenuUp),
+                bindings.label(Action::MenuDown),
+                bindings.label(Action::ExportLedger),
+                bindings.label(Action::Back),
+            ),
+            x,
+            ui_y + height - 20.0,
+            18.0,
+            DARKGRAY,
+        );
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -1,19 +1,24 @@
 use macroquad::prelude::*;
 use crate::models::StarType;
-
-pub enum GameState {
-    Menu,
-    Playing,
-}
+use crate::game::Game;
+use crate::input::{Action, Bindings};
+use crate::playing_screen::PlayingScreen;
+use crate::save::{self, SAVE_PATH};
+use crate::rules::Difficulty;
+use crate::scene::{Context, Scene, Transition};
+use crate::settings_screen::SettingsScreen;
+use crate::ui::{ListResponse, Ui};
 
 enum MenuScreen {
     StarSelection,
     PlanetCount,
+    Difficulty,
 }
 
 pub struct MenuSelection {
     pub star_type: StarType,
     pub planet_count: usize,
+    pub difficulty: Difficulty,
 }
 
 pub struct Menu {
@@ -22,6 +27,13 @@ pub struct Menu {
     star_types: Vec<StarType>,
     selected_star: Option<StarType>,
     planet_counts: Vec<usize>,
+    selected_planets: Option<usize>,
+    difficulties: Vec<Difficulty>,
+    clicked: bool,      // An option was clicked this frame, same as pressing Confirm
+    back_clicked: bool, // The back button was clicked, same as pressing Back
+    load_clicked: bool, // The continue button was clicked, same as pressing LoadGame
+    has_save: bool,     // A saved game was found when the menu opened
+    load_failed: bool,
 }
 
 impl Menu {
@@ -37,80 +49,99 @@ impl Menu {
             ],
             selected_star: None,
             planet_counts: (2..=9).collect(),
+            selected_planets: None,
+            difficulties: Difficulty::all(),
+            clicked: false,
+            back_clicked: false,
+            load_clicked: false,
+            has_save: std::path::Path::new(SAVE_PATH).exists(),
+            load_failed: false,
         }
     }
 
-    pub fn handle_input(&mut self) -> Option<MenuSelection> {
+    fn navigate(&mut self, bindings: &Bindings) -> Option<MenuSelection> {
+        // Mouse clicks were picked up while drawing
+        let confirm = bindings.is_pressed(Action::Confirm) || std::mem::take(&mut self.clicked);
+        let back = bindings.is_pressed(Action::Back) || std::mem::take(&mut self.back_clicked);
         match self.current_screen {
             MenuScreen::StarSelection => {
-                if is_key_pressed(KeyCode::Up) {
-                    if self.selected_index > 0 {
-                        self.selected_index -= 1;
-                    }
+                if bindings.is_pressed(Action::MenuUp) && self.selected_index > 0 {
+                    self.selected_index -= 1;
                 }
-                if is_key_pressed(KeyCode::Down) {
-                    if self.selected_index < self.star_types.len() - 1 {
-                        self.selected_index += 1;
-                    }
+                if bindings.is_pressed(Action::MenuDown) && self.selected_index < self.star_types.len() - 1 {
+                    self.selected_index += 1;
                 }
-                if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Space) {
+                if confirm {
                     self.selected_star = Some(self.star_types[self.selected_index]);
                     self.current_screen = MenuScreen::PlanetCount;
                     self.selected_index = 4; // Default to 6 planets (index 4 in 2-9 range)
                 }
             }
             MenuScreen::PlanetCount => {
-                if is_key_pressed(KeyCode::Up) {
-                    if self.selected_index > 0 {
-                        self.selected_index -= 1;
-                    }
+                if bindings.is_pressed(Action::MenuUp) && self.selected_index > 0 {
+                    self.selected_index -= 1;
                 }
-                if is_key_pressed(KeyCode::Down) {
-                    if self.selected_index < self.planet_counts.len() - 1 {
-                        self.selected_index += 1;
-                    }
+                if bindings.is_pressed(Action::MenuDown) && self.selected_index < self.planet_counts.len() - 1 {
+                    self.selected_index += 1;
                 }
-                if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Space) {
-                    if let Some(star_type) = self.selected_star {
-                        return Some(MenuSelection {
-                            star_type,
-                            planet_count: self.planet_counts[self.selected_index],
-                        });
-                    }
+                if confirm {
+                    self.selected_planets = Some(self.planet_counts[self.selected_index]);
+                    self.current_screen = MenuScreen::Difficulty;
+                    self.selected_index = 1; // Default to Normal
                 }
-                if is_key_pressed(KeyCode::Escape) {
+                if back {
                     self.current_screen = MenuScreen::StarSelection;
                     self.selected_index = 0;
                     self.selected_star = None;
                 }
             }
+            MenuScreen::Difficulty => {
+                if bindings.is_pressed(Action::MenuUp) && self.selected_index > 0 {
+                    self.selected_index -= 1;
+                }
+                if bindings.is_pressed(Action::MenuDown) && self.selected_index < self.difficulties.len() - 1 {
+                    self.selected_index += 1;
+                }
+                if confirm
+                    && let Some(star_type) = self.selected_star
+                    && let Some(planet_count) = self.selected_planets
+                {
+                    return Some(MenuSelection {
+                        star_type,
+                        planet_count,
+                        difficulty: self.difficulties[self.selected_index],
+                    });
+                }
+                if back {
+                    self.current_screen = MenuScreen::PlanetCount;
+                    self.selected_index = 4;
+                    self.selected_planets = None;
+                }
+            }
         }
         None
     }
 
-    pub fn draw(&self) {
-        let screen_w = screen_width();
-        let screen_h = screen_height();
-
-        // Title
-        let title = "SOLAR ECONOMY";
-        let title_size = 60.0;
-        let title_dims = measure_text(title, None, title_size as u16, 1.0);
-        draw_text(
-            title,
-            screen_w / 2.0 - title_dims.width / 2.0,
-            screen_h / 4.0,
-            title_size,
-            WHITE,
-        );
+    // Hovering an option selects it, clicking one confirms it
+    fn handle_list(&mut self, ui: &Ui, response: ListResponse) {
+        if let Some(i) = response.hovered
+            && ui.mouse_moved()
+        {
+            self.selected_index = i;
+        }
+        if let Some(i) = response.clicked {
+            self.selected_index = i;
+            self.clicked = true;
+        }
+    }
 
-        match self.current_screen {
-            MenuScreen::StarSelection => self.draw_star_selection(screen_w, screen_h),
-            MenuScreen::PlanetCount => self.draw_planet_count(screen_w, screen_h),
+    fn draw_back_button(&mut self, ui: &mut Ui, screen_h: f32) {
+        if ui.button(Rect::new(20.0, screen_h - 70.0, 100.0, 30.0), "< Back", true) {
+            self.back_clicked = true;
         }
     }
 
-    fn draw_star_selection(&self, screen_w: f32, screen_h: f32) {
+    fn draw_star_selection(&mut self, ui: &mut Ui, bindings: &Bindings, screen_w: f32, screen_h: f32) {
         // Subtitle
         let subtitle = "Choose Your Star";
         let subtitle_size = 30.0;
@@ -127,22 +158,14 @@ impl Menu {
         let start_y = screen_h / 2.0 - 50.0;
         let spacing = 60.0;
 
+        let names: Vec<String> = self.star_types.iter().map(|star_type| star_type.name().to_string()).collect();
+        let response = ui.list(screen_w / 2.0 - 170.0, start_y - 42.0, 380.0, spacing, &names, self.selected_index);
+        self.handle_list(ui, response);
+
         for (i, star_type) in self.star_types.iter().enumerate() {
             let y = start_y + i as f32 * spacing;
             let is_selected = i == self.selected_index;
 
-            // Selection indicator
-            if is_selected {
-                draw_text(">", screen_w / 2.0 - 200.0, y, 40.0, WHITE);
-            }
-
-            // Star type name
-            let text = star_type.name();
-            let text_size = if is_selected { 40.0 } else { 35.0 };
-            let color = if is_selected { WHITE } else { GRAY };
-
-            draw_text(text, screen_w / 2.0 - 150.0, y, text_size, color);
-
             // Draw a preview circle of the star
             let preview_x = screen_w / 2.0 + 150.0;
             let preview_radius = if is_selected { 20.0 } else { 15.0 };
@@ -161,11 +184,16 @@ impl Menu {
         }
 
         // Instructions
-        let instructions = "Use UP/DOWN arrows to select, ENTER to continue";
+        let instructions = format!(
+            "Use {}/{} or the mouse to select, {} or click to continue",
+            bindings.label(Action::MenuUp),
+            bindings.label(Action::MenuDown),
+            bindings.label(Action::Confirm),
+        );
         let inst_size = 20.0;
-        let inst_dims = measure_text(instructions, None, inst_size as u16, 1.0);
+        let inst_dims = measure_text(&instructions, None, inst_size as u16, 1.0);
         draw_text(
-            instructions,
+            &instructions,
             screen_w / 2.0 - inst_dims.width / 2.0,
             screen_h - 50.0,
             inst_size,
@@ -173,7 +201,7 @@ impl Menu {
         );
     }
 
-    fn draw_planet_count(&self, screen_w: f32, screen_h: f32) {
+    fn draw_planet_count(&mut self, ui: &mut Ui, bindings: &Bindings, screen_w: f32, screen_h: f32) {
         // Subtitle with selected star
         let subtitle = if let Some(star) = self.selected_star {
             format!("{} - Choose Planet Count", star.name())
@@ -194,22 +222,14 @@ impl Menu {
         let start_y = screen_h / 2.0 - 100.0;
         let spacing = 55.0;
 
+        let labels: Vec<String> = self.planet_counts.iter().map(|count| format!("{} Planets", count)).collect();
+        let response = ui.list(screen_w / 2.0 - 90.0, start_y - 38.0, 340.0, spacing, &labels, self.selected_index);
+        self.handle_list(ui, response);
+
         for (i, &count) in self.planet_counts.iter().enumerate() {
             let y = start_y + i as f32 * spacing;
             let is_selected = i == self.selected_index;
 
-            // Selection indicator
-            if is_selected {
-                draw_text(">", screen_w / 2.0 - 100.0, y, 40.0, WHITE);
-            }
-
-            // Planet count text
-            let text = format!("{} Planets", count);
-            let text_size = if is_selected { 40.0 } else { 35.0 };
-            let color = if is_selected { WHITE } else { GRAY };
-
-            draw_text(&text, screen_w / 2.0 - 70.0, y, text_size, color);
-
             // Draw preview planets (mini version)
             if is_selected {
                 let preview_start_x = screen_w / 2.0 + 120.0;
@@ -224,12 +244,64 @@ impl Menu {
             }
         }
 
+        self.draw_back_button(ui, screen_h);
+
+        // Instructions
+        let instructions = format!(
+            "{}/{} or mouse to select, {} or click to continue, {} to go back",
+            bindings.label(Action::MenuUp),
+            bindings.label(Action::MenuDown),
+            bindings.label(Action::Confirm),
+            bindings.label(Action::Back),
+        );
+        let inst_size = 20.0;
+        let inst_dims = measure_text(&instructions, None, inst_size as u16, 1.0);
+        draw_text(
+            &instructions,
+            screen_w / 2.0 - inst_dims.width / 2.0,
+            screen_h - 50.0,
+            inst_size,
+            DARKGRAY,
+        );
+    }
+
+    fn draw_difficulty(&mut self, ui: &mut Ui, bindings: &Bindings, screen_w: f32, screen_h: f32) {
+        let subtitle = "Choose Difficulty";
+        let subtitle_size = 30.0;
+        let subtitle_dims = measure_text(subtitle, None, subtitle_size as u16, 1.0);
+        draw_text(
+            subtitle,
+            screen_w / 2.0 - subtitle_dims.width / 2.0,
+            screen_h / 4.0 + 60.0,
+            subtitle_size,
+            GRAY,
+        );
+
+        let start_y = screen_h / 2.0 - 50.0;
+        let spacing = 60.0;
+
+        let names: Vec<String> = self.difficulties.iter().map(|difficulty| difficulty.name().to_string()).collect();
+        let response = ui.list(screen_w / 2.0 - 170.0, start_y - 42.0, 480.0, spacing, &names, self.selected_index);
+        self.handle_list(ui, response);
+        if let Some(difficulty) = self.difficulties.get(self.selected_index) {
+            let y = start_y + self.selected_index as f32 * spacing;
+            draw_text(difficulty.description(), screen_w / 2.0 + 20.0, y - 6.0, 16.0, LIGHTGRAY);
+        }
+
+        self.draw_back_button(ui, screen_h);
+
         // Instructions
-        let instructions = "UP/DOWN to select, ENTER to start, ESC to go back";
+        let instructions = format!(
+            "{}/{} or mouse to select, {} or click to start, {} to go back",
+            bindings.label(Action::MenuUp),
+            bindings.label(Action::MenuDown),
+            bindings.label(Action::Confirm),
+            bindings.label(Action::Back),
+        );
         let inst_size = 20.0;
-        let inst_dims = measure_text(instructions, None, inst_size as u16, 1.0);
+        let inst_dims = measure_text(&instructions, None, inst_size as u16, 1.0);
         draw_text(
-            instructions,
+            &instructions,
             screen_w / 2.0 - inst_dims.width / 2.0,
             screen_h - 50.0,
             inst_size,
@@ -237,3 +309,65 @@ impl Menu {
         );
     }
 }
+
+// The menu is the first scene and hands over to the flight view once a run starts
+impl Scene for Menu {
+    fn handle_input(&mut self, ctx: &mut Context) -> Transition {
+        if ctx.settings.bindings.is_pressed(Action::OpenSettings) {
+            return Transition::Push(Box::new(SettingsScreen::new()));
+        }
+        let load = ctx.settings.bindings.is_pressed(Action::LoadGame) || std::mem::take(&mut self.load_clicked);
+        if load && self.has_save {
+            match save::load(SAVE_PATH) {
+                Some(game) => {
+                    ctx.game = Some(game);
+                    return Transition::Replace(Box::new(PlayingScreen::new()));
+                }
+                None => self.load_failed = true,
+            }
+        }
+        match self.navigate(&ctx.settings.bindings) {
+            Some(selection) => {
+                ctx.game = Some(Game::new(&selection, &ctx.settings));
+                Transition::Replace(Box::new(PlayingScreen::new()))
+            }
+            None => Transition::None,
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context) {
+        let ui = &mut ctx.ui;
+        let bindings = &ctx.settings.bindings;
+        let screen_w = screen_width();
+        let screen_h = screen_height();
+
+        // Title
+        let title = "SOLAR ECONOMY";
+        let title_size = 60.0;
+        let title_dims = measure_text(title, None, title_size as u16, 1.0);
+        draw_text(
+            title,
+            screen_w / 2.0 - title_dims.width / 2.0,
+            screen_h / 4.0,
+            title_size,
+            WHITE,
+        );
+
+        match self.current_screen {
+            MenuScreen::StarSelection => self.draw_star_selection(ui, bindings, screen_w, screen_h),
+            MenuScreen::PlanetCount => self.draw_planet_count(ui, bindings, screen_w, screen_h),
+            MenuScreen::Difficulty => self.draw_difficulty(ui, bindings, screen_w, screen_h),
+        }
+        draw_text(&format!("Press {} for settings", ctx.settings.bindings.label(Action::OpenSettings)), 10.0, 20.0, 18.0, DARKGRAY);
+
+        if self.has_save {
+            let label = format!("[{}] Continue saved game", bindings.label(Action::LoadGame));
+            if ui.button(Rect::new(screen_w - 250.0, 10.0, 240.0, 28.0), &label, !self.load_failed) {
+                self.load_clicked = true;
+            }
+            if self.load_failed {
+                draw_text("The saved game couldn't be read", screen_w - 250.0, 58.0, 16.0, ORANGE);
+            }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::VecDeque;
+use macroquad::prelude::*;
+
+// How long a message stays up as a toast, and how many can be up at once
+const TOAST_DURATION: f32 = 5.0;
+const MAX_TOASTS: usize = 4;
+// Oldest messages are dropped past this
+const MAX_LOG_ENTRIES: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Info => "Info",
+            Severity::Success => "Success",
+            Severity::Warning => "Warning",
+            Severity::Error => "Error",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            Severity::Info => SKYBLUE,
+            Severity::Success => GREEN,
+            Severity::Warning => ORANGE,
+            Severity::Error => RED,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub time: f32,
+    pub severity: Severity,
+    pub text: String,
+}
+
+// Everything the game has told the player, newest last. Anything holding
+// the log can post to it; recent messages are also shown as toasts.
+pub struct MessageLog {
+    pub messages: VecDeque<Message>,
+    now: f32,
+}
+
+impl MessageLog {
+    pub fn new() -> Self {
+        Self {
+            messages: VecDeque::new(),
+            now: 0.0,
+        }
+    }
+
+    // Game time stamped on everything posted from now on
+    pub fn set_time(&mut self, now: f32) {
+        self.now = now;
+    }
+
+    pub fn post(&mut self, severity: Severity, text: impl Into<String>) {
+        self.messages.push_back(Message { time: self.now, severity, text: text.into() });
+        if self.messages.len() > MAX_LOG_ENTRIES {
+            self.messages.pop_front();
+        }
+    }
+
+    pub fn info(&mut self, text: impl Into<String>) {
+        self.post(Severity::Info, text);
+    }
+
+    pub fn success(&mut self, text: impl Into<String>) {
+        self.post(Severity::Success, text);
+    }
+
+    pub fn warning(&mut self, text: impl Into<String>) {
+        self.post(Severity::Warning, text);
+    }
+
+    pub fn error(&mut self, text: impl Into<String>) {
+        self.post(Severity::Error, text);
+    }
+
+    // Recent messages stacked down from the top of the screen, newest first,
+    // each fading out over its last second
+    pub fn draw_toasts(&self, screen_w: f32) {
+        let recent = self.messages
+            .iter()
+            .rev()
+            .take_while(|m| self.now - m.time < TOAST_DURATION)
+            .take(MAX_TOASTS);
+
+        for (i, message) in recent.enumerate() {
+            let alpha = (TOAST_DURATION - (self.now - message.time)).min(1.0);
+            let dims = measure_text(&message.text, None, 20, 1.0);
+            let x = screen_w / 2.0 - dims.width / 2.0;
+            let y = 80.0 + i as f32 * 34.0;
+            draw_rectangle(x - 12.0, y - 20.0, dims.width + 24.0, 30.0, Color::new(0.0, 0.0, 0.0, 0.7 * alpha));
+            draw_rectangle(x - 12.0, y - 20.0, 4.0, 30.0, Color { a: alpha, ..message.severity.color() });
+            draw_text(&message.text, x, y, 20.0, Color { a: alpha, ..message.severity.color() });
+        }
+    }
+}
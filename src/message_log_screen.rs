@@ -0,0 +1,96 @@
+use macroquad::prelude::*;
+use crate::input::Action;
+use crate::message_log::{Message, MessageLog, Severity};
+use crate::scene::{Context, Scene, Transition};
+
+// Overlay for scrolling back through everything the game has said
+pub struct MessageLogScreen {
+    scroll: usize,
+    filter: Option<Severity>,
+}
+
+impl MessageLogScreen {
+    pub fn new() -> Self {
+        Self {
+            scroll: 0,
+            filter: None,
+        }
+    }
+
+    // Messages passing the filter, newest first so the top of the list is
+    // what just happened
+    fn shown<'a>(&self, log: &'a MessageLog) -> Vec<&'a Message> {
+        log.messages
+            .iter()
+            .rev()
+            .filter(|m| self.filter.is_none_or(|severity| m.severity == severity))
+            .collect()
+    }
+}
+
+impl Scene for MessageLogScreen {
+    fn handle_input(&mut self, ctx: &mut Context) -> Transition {
+        let bindings = &ctx.settings.bindings;
+        if bindings.is_pressed(Action::NextTab) {
+            // Cycle All -> Info -> Success -> Warning -> Error -> All
+            self.filter = match self.filter {
+                None => Some(Severity::Info),
+                Some(Severity::Info) => Some(Severity::Success),
+                Some(Severity::Success) => Some(Severity::Warning),
+                Some(Severity::Warning) => Some(Severity::Error),
+                Some(Severity::Error) => None,
+            };
+            self.scroll = 0;
+        }
+        if bindings.is_pressed(Action::MenuUp) {
+            self.scroll = self.scroll.saturating_sub(1);
+        }
+        if bindings.is_pressed(Action::MenuDown) {
+            let count = ctx.game.as_ref().map_or(0, |game| self.shown(&game.messages).len());
+            self.scroll = (self.scroll + 1).min(count.saturating_sub(1));
+        }
+        Transition::pop_if(bindings.is_pressed(Action::Back) || bindings.is_pressed(Action::OpenMessages))
+    }
+
+    fn draw(&mut self, ctx: &mut Context) {
+        let Some(game) = ctx.game.as_ref() else { return };
+        let log = &game.messages;
+        let ui_x = screen_width() / 2.0 - 380.0;
+        let ui_y = 80.0;
+        let width = 760.0;
+        let height = screen_height() - 160.0;
+        draw_rectangle(ui_x, ui_y, width, height, Color::new(0.0, 0.0, 0.05, 0.9));
+        draw_rectangle_lines(ui_x, ui_y, width, height, 2.0, SKYBLUE);
+        draw_text("MESSAGE LOG", ui_x + 20.0, ui_y + 35.0, 30.0, YELLOW);
+        draw_text(self.filter.map(|s| s.name()).unwrap_or("All"), ui_x + 240.0, ui_y + 35.0, 22.0, WHITE);
+
+        let x = ui_x + 20.0;
+        let y = ui_y + 75.0;
+        let rows = ((height - 120.0) / 22.0).max(1.0) as usize;
+        let shown = self.shown(log);
+        if shown.is_empty() {
+            draw_text("No messages", x, y, 18.0, GRAY);
+        }
+        for (row, message) in shown.iter().skip(self.scroll).take(rows).enumerate() {
+            let row_y = y + row as f32 * 22.0;
+            let color = message.severity.color();
+            draw_text(&format!("{:>5.0}s", message.time), x, row_y, 16.0, LIGHTGRAY);
+            draw_text(message.severity.name(), x + 56.0, row_y, 16.0, color);
+            draw_text(&message.text, x + 130.0, row_y, 16.0, WHITE);
+        }
+
+        let bindings = &ctx.settings.bindings;
+        let hint = format!(
+            "{} filter  {}/{} scroll  {} close",
+            bindings.label(Action::NextTab),
+            bindings.label(Action::MenuUp),
+            bindings.label(Action::MenuDown),
+            bindings.label(Action::Back),
+        );
+        draw_text(&hint, x, ui_y + height - 20.0, 18.0, DARKGRAY);
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,90 @@
+use macroquad::prelude::*;
+use super::orbit::Orbit;
+use crate::economy::RARE_ASTEROID_CHANCE;
+
+pub struct Asteroid {
+    pub orbit: Orbit,
+    pub radius: f32,
+    pub ore: i32,      // Units left to mine
+    pub rare: bool,    // Rich in rare ore
+    sides: u8,
+    spin: f32,
+    rotation: f32,
+}
+
+impl Asteroid {
+    pub fn position(&self, center: Vec2) -> Vec2 {
+        self.orbit.position(center)
+    }
+
+    pub fn is_depleted(&self) -> bool {
+        self.ore <= 0
+    }
+}
+
+// A ring of asteroids orbiting the star between two planet orbits
+pub struct AsteroidBelt {
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub asteroids: Vec<Asteroid>,
+}
+
+impl AsteroidBelt {
+    pub fn new(inner_radius: f32, outer_radius: f32, count: usize) -> Self {
+        let mut asteroids = Vec::with_capacity(count);
+        for _ in 0..count {
+            let orbital_radius = rand::gen_range(inner_radius, outer_radius);
+            let radius = rand::gen_range(4.0, 12.0);
+            let rare = rand::gen_range(0.0, 1.0) < RARE_ASTEROID_CHANCE;
+
+            asteroids.push(Asteroid {
+                // Belts sit between planet orbits, so they move at a similar slow pace
+                orbit: Orbit::new(orbital_radius, 60.0 / orbital_radius, rand::gen_range(0.0, std::f32::consts::TAU)),
+                radius,
+                ore: (radius * 0.8) as i32 + 2,
+                rare,
+                sides: rand::gen_range(5, 8),
+                spin: rand::gen_range(-1.0, 1.0),
+                rotation: rand::gen_range(0.0, 360.0),
+            });
+        }
+
+        Self {
+            inner_radius,
+            outer_radius,
+            asteroids,
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for asteroid in &mut self.asteroids {
+            asteroid.orbit.update(dt);
+            asteroid.rotation += asteroid.spin * 40.0 * dt;
+        }
+        self.asteroids.retain(|a| !a.is_depleted());
+    }
+
+    pub fn draw(&self, center: Vec2, camera_offset: Vec2) {
+        // Faint dust band marking the belt's extent
+        let screen_center = center - camera_offset;
+        let width = self.outer_radius - self.inner_radius;
+        draw_circle_lines(
+            screen_center.x,
+            screen_center.y,
+            self.inner_radius + width / 2.0,
+            width,
+            Color::new(0.4, 0.35, 0.3, 0.06),
+        );
+
+        for asteroid in &self.asteroids {
+            let screen_pos = asteroid.position(center) - camera_offset;
+            let color = if asteroid.rare {
+                Color::new(0.6, 0.45, 0.7, 1.0)
+            } else {
+                Color::new(0.45, 0.4, 0.35, 1.0)
+            };
+            draw_poly(screen_pos.x, screen_pos.y, asteroid.sides, asteroid.radius, asteroid.rotation, color);
+            draw_poly_lines(screen_pos.x, screen_pos.y, asteroid.sides, asteroid.radius, asteroid.rotation, 1.0, DARKGRAY);
+        }
+    }
+}
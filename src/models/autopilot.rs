@@ -0,0 +1,146 @@
+use macroquad::prelude::*;
+use super::{SolarSystem, DockId, Spaceship};
+use super::spaceship::SpaceshipState;
+use crate::economy::{ProductType, TradeService, TradeSide};
+use crate::ledger::{Ledger, Transaction, TransactionKind};
+use crate::rules::GameRules;
+use crate::economy::{
+    STAR_HEAT_RADIUS_FACTOR, REPUTATION_PER_TRADE_UNIT,
+};
+
+const DWELL_TIME: f32 = 2.0;          // Seconds spent docked at each stop
+const FUEL_RESERVE: f32 = 30.0;       // Refuel up to this before leaving a stop
+const FOOD_RESERVE: i32 = 15;         // Restock food up to this before leaving a stop
+const CASH_RESERVE: i32 = 20;         // Never spend the last of this on cargo
+const STAR_AVOIDANCE_MARGIN: f32 = 60.0;
+
+// Flies a ship around a fixed loop of stops, buying cheap and selling on arrival
+pub struct Autopilot {
+    pub route: Vec<DockId>,
+    pub next_stop: usize,
+    dwell_timer: f32,
+}
+
+impl Autopilot {
+    pub fn new(route: Vec<DockId>) -> Self {
+        Self {
+            route,
+            next_stop: 0,
+            dwell_timer: 0.0,
+        }
+    }
+
+    pub fn target(&self) -> Option<DockId> {
+        self.route.get(self.next_stop).copied()
+    }
+
+    fn advance(&mut self) {
+        self.next_stop = (self.next_stop + 1) % self.route.len().max(1);
+        self.dwell_timer = 0.0;
+    }
+
+    pub fn drive(&mut self, ship: &mut Spaceship, dt: f32, system: &mut SolarSystem, ledger: &mut Ledger, rules: &GameRules) {
+        let Some(target) = self.target() else { return };
+        ship.mining = false;
+
+        match ship.state {
+            SpaceshipState::Flying => {
+                // Skip stops whose owners won't let the ship land
+                if !system.landing_allowed(target) {
+                    self.advance();
+                    return;
+                }
+                if ship.can_dock_at(target, system) {
+                    ship.begin_landing(target, system);
+                    return;
+                }
+                let aim = self.intercept_point(ship, target, system);
+                let direction = (aim - ship.position).normalize_or_zero();
+                ship.velocity = direction * ship.effective_speed();
+                if direction != Vec2::ZERO {
+                    ship.rotation = direction.y.atan2(direction.x);
+                }
+            }
+            SpaceshipState::Landed => {
+                // Landed somewhere off-route (the player may have flown it there)
+                if ship.docked_at != Some(target) {
+                    ship.begin_takeoff(system);
+                    return;
+                }
+
+                if self.dwell_timer == 0.0 {
+                    let next = self.route[(self.next_stop + 1) % self.route.len()];
+                    Self::restock(ship, target, next, system, ledger, rules);
+                }
+                self.dwell_timer += dt;
+                if self.dwell_timer >= DWELL_TIME {
+                    self.advance();
+                    ship.begin_takeoff(system);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Lead the target to where its orbit will have carried it, then swing
+    // wide of the star if the straight line would cook the ship
+    fn intercept_point(&self, ship: &Spaceship, target: DockId, system: &SolarSystem) -> Vec2 {
+        let aim = system
+            .intercept(ship.position, ship.effective_speed(), target)
+            .map_or(ship.position, |(aim, _)| aim);
+
+        let star = &system.star;
+        let avoid_radius = star.radius * STAR_HEAT_RADIUS_FACTOR + STAR_AVOIDANCE_MARGIN;
+        let path = aim - ship.position;
+        let along = (star.position - ship.position).dot(path) / path.length_squared().max(1.0);
+        if (0.0..1.0).contains(&along) {
+            let closest = ship.position + path * along;
+            let offset = closest - star.position;
+            if offset.length() < avoid_radius {
+                let side = offset.normalize_or(path.perp().normalize_or(Vec2::Y));
+                return star.position + side * avoid_radius * 1.2;
+            }
+        }
+        aim
+    }
+
+    // Top up fuel and food, then fill the hold with whatever is made here
+    // and can be carried legally into the next stop
+    fn restock(ship: &mut Spaceship, dock: DockId, next: DockId, system: &mut SolarSystem, ledger: &mut Ledger, rules: &GameRules) {
+        let Some(body) = system.dockable_mut(dock) else { return };
+        let location = body.name().to_string();
+        let for_sale = body.market().for_sale();
+        let mut trade = TradeService::new(body.market_mut(), &mut ship.inventory, rules);
+
+        let mut fuel_spent = 0;
+        while trade.inventory().fuel < FUEL_RESERVE
+            && let Ok(cost) = trade.buy_fuel()
+        {
+            fuel_spent += cost;
+        }
+        ledger.record(Transaction::new(TransactionKind::Fuel, &ship.name, &location, -fuel_spent));
+
+        let mut food_spent = 0;
+        while trade.inventory().food < FOOD_RESERVE
+            && let Ok(cost) = trade.buy_food()
+        {
+            food_spent += cost;
+        }
+        ledger.record(Transaction::new(TransactionKind::Food, &ship.name, &location, -food_spent));
+
+        let legal: Vec<ProductType> = for_sale.into_iter().filter(|&p| !system.is_contraband(next, p)).collect();
+        let mut units_bought = 0;
+        for product in legal {
+            let terms = system.trade_terms(dock, product, TradeSide::Buy);
+            let Some(market) = system.dockable_mut(dock).map(|body| body.market_mut()) else { break };
+            let mut trade = TradeService::new(market, &mut ship.inventory, rules);
+
+            let quantity = trade.affordable_quantity(product, terms, CASH_RESERVE);
+            if let Ok(quote) = trade.buy(product, quantity, terms) {
+                units_bought += quote.quantity;
+                ledger.record_trade(&ship.name, &location, &quote);
+            }
+        }
+        system.adjust_reputation(dock, units_bought as f32 * REPUTATION_PER_TRADE_UNIT);
+    }
+}
@@ -0,0 +1,59 @@
+use macroquad::prelude::*;
+
+const HUNTER_SPEED: f32 = 85.0;       // A little slower than an undamaged ship
+const HUNTER_SIZE: f32 = 12.0;
+const HUNTER_ATTACK_RANGE: f32 = 25.0;
+const HUNTER_ATTACK_COOLDOWN: f32 = 1.5;
+pub const HUNTER_DAMAGE: f32 = 8.0;
+
+// Sent by the banks after repeated missed loan payments
+pub struct BountyHunter {
+    pub position: Vec2,
+    pub rotation: f32,
+    attack_cooldown: f32,
+}
+
+impl BountyHunter {
+    // Spawn somewhere out of sight around the target
+    pub fn spawn_near(target: Vec2) -> Self {
+        let angle = rand::gen_range(0.0, std::f32::consts::TAU);
+        Self {
+            position: target + Vec2::from_angle(angle) * 900.0,
+            rotation: angle + std::f32::consts::PI,
+            attack_cooldown: HUNTER_ATTACK_COOLDOWN,
+        }
+    }
+
+    // Chase the target, returns true when close enough to land a hit this frame
+    pub fn update(&mut self, dt: f32, target: Vec2) -> bool {
+        self.attack_cooldown = (self.attack_cooldown - dt).max(0.0);
+
+        let to_target = target - self.position;
+        if to_target.length() > HUNTER_ATTACK_RANGE {
+            let direction = to_target.normalize();
+            self.position += direction * HUNTER_SPEED * dt;
+            self.rotation = direction.y.atan2(direction.x);
+            return false;
+        }
+
+        if self.attack_cooldown <= 0.0 {
+            self.attack_cooldown = HUNTER_ATTACK_COOLDOWN;
+            return true;
+        }
+        false
+    }
+
+    pub fn draw(&self, camera_offset: Vec2) {
+        let screen_pos = self.position - camera_offset;
+        let angle = self.rotation;
+
+        let tip = screen_pos + Vec2::from_angle(angle) * HUNTER_SIZE;
+        let left = screen_pos + Vec2::from_angle(angle + 2.3) * HUNTER_SIZE * 0.7;
+        let right = screen_pos + Vec2::from_angle(angle - 2.3) * HUNTER_SIZE * 0.7;
+
+        draw_triangle(tip, left, right, Color::new(0.6, 0.1, 0.1, 1.0));
+        draw_line(tip.x, tip.y, left.x, left.y, 2.0, RED);
+        draw_line(left.x, left.y, right.x, right.y, 2.0, RED);
+        draw_line(right.x, right.y, tip.x, tip.y, 2.0, RED);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::economy::Market;
+use super::{Moon, Planet, Station};
+
+// Identifies a landable body by its place in the orbit hierarchy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DockId {
+    Planet(usize),
+    Moon(usize, usize),
+    PlanetStation(usize, usize),
+    MoonStation(usize, usize, usize),
+}
+
+impl DockId {
+    // The planet this body ultimately orbits
+    pub fn planet_index(&self) -> usize {
+        match *self {
+            DockId::Planet(p)
+            | DockId::Moon(p, _)
+            | DockId::PlanetStation(p, _)
+            | DockId::MoonStation(p, _, _) => p,
+        }
+    }
+
+    pub fn is_station(&self) -> bool {
+        matches!(self, DockId::PlanetStation(..) | DockId::MoonStation(..))
+    }
+}
+
+// Anything the ship can land on and trade with
+pub trait Dockable {
+    fn name(&self) -> &str;
+    fn radius(&self) -> f32;
+    fn market(&self) -> &Market;
+    fn market_mut(&mut self) -> &mut Market;
+}
+
+impl Dockable for Planet {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    fn market(&self) -> &Market {
+        &self.market
+    }
+
+    fn market_mut(&mut self) -> &mut Market {
+        &mut self.market
+    }
+}
+
+impl Dockable for Moon {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    fn market(&self) -> &Market {
+        &self.market
+    }
+
+    fn market_mut(&mut self) -> &mut Market {
+        &mut self.market
+    }
+}
+
+impl Dockable for Station {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn radius(&self) -> f32 {
+        self.size
+    }
+
+    fn market(&self) -> &Market {
+        &self.market
+    }
+
+    fn market_mut(&mut self) -> &mut Market {
+        &mut self.market
+    }
+}
@@ -0,0 +1,33 @@
+use crate::economy::{MINING_WEAR_PER_UNIT, SMUGGLER_COMPARTMENT_CAPACITY};
+
+// Optional ship module used to extract ore from asteroids
+#[derive(Debug, Clone, Copy)]
+pub struct MiningLaser {
+    pub condition: f32, // 1.0 = brand new, 0.0 = broken
+}
+
+impl MiningLaser {
+    pub fn new() -> Self {
+        Self { condition: 1.0 }
+    }
+
+    pub fn is_broken(&self) -> bool {
+        self.condition <= 0.0
+    }
+
+    pub fn wear(&mut self) {
+        self.condition = (self.condition - MINING_WEAR_PER_UNIT).max(0.0);
+    }
+}
+
+// Shielded space in the hold that customs scanners usually miss
+#[derive(Debug, Clone, Copy)]
+pub struct SmugglerCompartment {
+    pub capacity: i32,
+}
+
+impl SmugglerCompartment {
+    pub fn new() -> Self {
+        Self { capacity: SMUGGLER_COMPARTMENT_CAPACITY }
+    }
+}
@@ -0,0 +1,116 @@
+use macroquad::prelude::*;
+use crate::economy::{ProductType, MAX_REPUTATION, REPUTATION_RECOVERY_PER_MINUTE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Standing {
+    Hostile,
+    Unfriendly,
+    Neutral,
+    Friendly,
+    Allied,
+}
+
+impl Standing {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Standing::Hostile => "Hostile",
+            Standing::Unfriendly => "Unfriendly",
+            Standing::Neutral => "Neutral",
+            Standing::Friendly => "Friendly",
+            Standing::Allied => "Allied",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            Standing::Hostile => RED,
+            Standing::Unfriendly => ORANGE,
+            Standing::Neutral => LIGHTGRAY,
+            Standing::Friendly => GREEN,
+            Standing::Allied => SKYBLUE,
+        }
+    }
+}
+
+// A power controlling a group of planets (and everything orbiting them).
+// `reputation` is how it regards the player, from -MAX_REPUTATION to MAX_REPUTATION.
+pub struct Faction {
+    pub name: String,
+    pub color: Color,
+    pub reputation: f32,
+    pub contraband: Vec<ProductType>, // Illegal to carry into this faction's territory
+}
+
+impl Faction {
+    pub fn new(name: String, color: Color) -> Self {
+        Self {
+            name,
+            color,
+            reputation: 0.0,
+            contraband: Vec::new(),
+        }
+    }
+
+    pub fn standing(&self) -> Standing {
+        match self.reputation {
+            r if r <= -50.0 => Standing::Hostile,
+            r if r < -15.0 => Standing::Unfriendly,
+            r if r < 15.0 => Standing::Neutral,
+            r if r < 50.0 => Standing::Friendly,
+            _ => Standing::Allied,
+        }
+    }
+
+    pub fn adjust_reputation(&mut self, delta: f32) {
+        self.reputation = (self.reputation + delta).clamp(-MAX_REPUTATION, MAX_REPUTATION);
+    }
+
+    // Grudges fade, so a faction that turned hostile lets the player land
+    // again in time. Good standing has to be kept up by trading.
+    pub fn update(&mut self, dt: f32) {
+        if self.reputation < 0.0 {
+            self.reputation = (self.reputation + REPUTATION_RECOVERY_PER_MINUTE * dt / 60.0).min(0.0);
+        }
+    }
+
+    pub fn is_contraband(&self, product: ProductType) -> bool {
+        self.contraband.contains(&product)
+    }
+
+    pub fn allows_landing(&self) -> bool {
+        self.standing() != Standing::Hostile
+    }
+
+    // Multiplier on what the player pays in this faction's markets
+    pub fn buy_price_factor(&self) -> f32 {
+        match self.standing() {
+            Standing::Hostile => 1.25,
+            Standing::Unfriendly => 1.1,
+            Standing::Neutral => 1.0,
+            Standing::Friendly => 0.95,
+            Standing::Allied => 0.9,
+        }
+    }
+
+    // Multiplier on what the player is paid in this faction's markets
+    pub fn sell_price_factor(&self) -> f32 {
+        match self.standing() {
+            Standing::Hostile => 0.8,
+            Standing::Unfriendly => 0.9,
+            Standing::Neutral => 1.0,
+            Standing::Friendly => 1.05,
+            Standing::Allied => 1.1,
+        }
+    }
+
+    // Multiplier on the tariffs and docking fees of this faction's markets
+    pub fn tariff_factor(&self) -> f32 {
+        match self.standing() {
+            Standing::Hostile => 2.0,
+            Standing::Unfriendly => 1.5,
+            Standing::Neutral => 1.0,
+            Standing::Friendly => 0.5,
+            Standing::Allied => 0.0,
+        }
+    }
+}
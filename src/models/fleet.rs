@@ -0,0 +1,103 @@
+use macroquad::prelude::*;
+use super::{SolarSystem, Spaceship};
+use crate::input::Bindings;
+use crate::ledger::Ledger;
+use crate::message_log::MessageLog;
+use crate::rules::{GameRules, ProductPrices};
+
+// Every ship the player owns. One is under direct control; the rest fly
+// their assigned trade routes (or sit idle) in the same simulation.
+// The fleet shares one purse. It sits in the active ship's inventory, so
+// trading, the bank and the HUD all see it, and each ship on autopilot
+// holds it while it takes its turn.
+pub struct Fleet {
+    pub ships: Vec<Spaceship>,
+    pub active: usize,
+}
+
+impl Fleet {
+    pub fn new(flagship: Spaceship) -> Self {
+        Self {
+            ships: vec![flagship],
+            active: 0,
+        }
+    }
+
+    pub fn purse(&self) -> i32 {
+        self.active().inventory.money
+    }
+
+    pub fn active(&self) -> &Spaceship {
+        &self.ships[self.active]
+    }
+
+    pub fn active_mut(&mut self) -> &mut Spaceship {
+        &mut self.ships[self.active]
+    }
+
+    pub fn switch_to(&mut self, index: usize) -> bool {
+        if !self.ships.get(index).is_some_and(|ship| ship.is_operational()) {
+            return false;
+        }
+        // The purse goes wherever the player goes
+        let purse = std::mem::take(&mut self.ships[self.active].inventory.money);
+        self.ships[index].inventory.money += purse;
+        self.active = index;
+        true
+    }
+
+    // First ship after the active one that can still fly
+    pub fn next_operational(&self) -> Option<usize> {
+        (1..self.ships.len())
+            .map(|offset| (self.active + offset) % self.ships.len())
+            .find(|&i| self.ships[i].is_operational())
+    }
+
+    pub fn add(&mut self, ship: Spaceship) {
+        self.ships.push(ship);
+    }
+
+    pub fn next_ship_name(&self) -> String {
+        format!("Trader {}", self.ships.len() + 1)
+    }
+
+    // What the ship has earned for the purse, plus the value of its hold
+    pub fn profit(&self, ship: &Spaceship, ledger: &Ledger, prices: &ProductPrices) -> i32 {
+        let cargo_value: i32 = ship.inventory.cargo.iter().map(|(&product, amount)| prices.sell_price(product) * amount).sum();
+        ledger.earnings(&ship.name) + cargo_value
+    }
+
+    // Player input drives the active ship; everyone else runs on autopilot
+    pub fn update(
+        &mut self,
+        dt: f32,
+        system: &mut SolarSystem,
+        ledger: &mut Ledger,
+        log: &mut MessageLog,
+        rules: &GameRules,
+        player_input: Option<&Bindings>, // None while a screen has the keyboard
+    ) {
+        let mut purse = std::mem::take(&mut self.ships[self.active].inventory.money);
+        for (i, ship) in self.ships.iter_mut().enumerate() {
+            ship.update_particles(dt);
+            if !ship.is_operational() {
+                continue;
+            }
+            ship.inventory.money = purse;
+            if i == self.active {
+                if let Some(bindings) = player_input {
+                    ship.handle_input(system, bindings);
+                } else {
+                    // An open screen has the keyboard, so hold position
+                    ship.velocity = Vec2::ZERO;
+                    ship.mining = false;
+                }
+            } else {
+                ship.run_autopilot(dt, system, ledger, rules);
+            }
+            ship.update(dt, system, ledger, log, rules);
+            purse = std::mem::take(&mut ship.inventory.money);
+        }
+        self.ships[self.active].inventory.money = purse;
+    }
+}
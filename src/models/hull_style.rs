@@ -0,0 +1,115 @@
+use macroquad::prelude::*;
+
+// Outlines in units of the ship's size, nose along +x. Each is star-shaped
+// around the ship's centre so it can be filled as a fan from there.
+const ARROW_OUTLINE: [(f32, f32); 5] = [(1.0, 0.0), (-0.3, 0.4), (-0.5, 0.36), (-0.5, -0.36), (-0.3, -0.4)];
+const HAULER_OUTLINE: [(f32, f32); 8] = [
+    (0.85, 0.2), (0.85, -0.2), (0.5, -0.45), (-0.65, -0.5), (-0.75, -0.3), (-0.75, 0.3), (-0.65, 0.5), (0.5, 0.45),
+];
+const INTERCEPTOR_OUTLINE: [(f32, f32); 8] = [
+    (1.15, 0.0), (0.05, 0.16), (-0.6, 0.62), (-0.42, 0.12), (-0.55, 0.0), (-0.42, -0.12), (-0.6, -0.62), (0.05, -0.16),
+];
+
+// How a ship looks. Purely cosmetic; the flagship's is picked in settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HullStyle {
+    Arrow,
+    Hauler,
+    Interceptor,
+}
+
+impl HullStyle {
+    pub fn all() -> Vec<HullStyle> {
+        vec![HullStyle::Arrow, HullStyle::Hauler, HullStyle::Interceptor]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HullStyle::Arrow => "Arrow",
+            HullStyle::Hauler => "Hauler",
+            HullStyle::Interceptor => "Interceptor",
+        }
+    }
+
+    pub fn next(&self) -> HullStyle {
+        match self {
+            HullStyle::Arrow => HullStyle::Hauler,
+            HullStyle::Hauler => HullStyle::Interceptor,
+            HullStyle::Interceptor => HullStyle::Arrow,
+        }
+    }
+
+    pub fn previous(&self) -> HullStyle {
+        self.next().next()
+    }
+
+    pub fn parse(name: &str) -> Option<HullStyle> {
+        HullStyle::all().into_iter().find(|h| h.name() == name)
+    }
+
+    fn outline(&self) -> &'static [(f32, f32)] {
+        match self {
+            HullStyle::Arrow => &ARROW_OUTLINE,
+            HullStyle::Hauler => &HAULER_OUTLINE,
+            HullStyle::Interceptor => &INTERCEPTOR_OUTLINE,
+        }
+    }
+
+    // Hull plating and the trim around it
+    fn colors(&self) -> (Color, Color) {
+        match self {
+            HullStyle::Arrow => (WHITE, SKYBLUE),
+            HullStyle::Hauler => (Color::new(0.75, 0.7, 0.6, 1.0), ORANGE),
+            HullStyle::Interceptor => (Color::new(0.55, 0.6, 0.7, 1.0), RED),
+        }
+    }
+
+    // Where the nozzles sit, in the same units as the outline
+    pub fn engines(&self) -> &'static [(f32, f32)] {
+        match self {
+            HullStyle::Arrow => &[(-0.5, 0.0)],
+            HullStyle::Hauler => &[(-0.75, 0.22), (-0.75, -0.22)],
+            HullStyle::Interceptor => &[(-0.55, 0.0)],
+        }
+    }
+
+    fn cockpit(&self) -> (f32, f32) {
+        match self {
+            HullStyle::Arrow => (0.3, 0.0),
+            HullStyle::Hauler => (0.6, 0.0),
+            HullStyle::Interceptor => (0.45, 0.0),
+        }
+    }
+
+    // `thrust` lights the nozzles
+    pub fn draw(&self, pos: Vec2, rotation: f32, size: f32, thrust: bool) {
+        let facing = Vec2::from_angle(rotation);
+        let to_screen = |(x, y): (f32, f32)| pos + facing.rotate(vec2(x, y)) * size;
+        let (plating, trim) = self.colors();
+
+        let outline: Vec<Vec2> = self.outline().iter().map(|&p| to_screen(p)).collect();
+        for (i, &point) in outline.iter().enumerate() {
+            let next = outline[(i + 1) % outline.len()];
+            draw_triangle(pos, point, next, plating);
+        }
+
+        // Panel lines from the centre out to each corner give the plating some depth
+        let panel = Color::new(trim.r, trim.g, trim.b, 0.25);
+        for &point in outline.iter().skip(1) {
+            draw_line(pos.x, pos.y, point.x, point.y, 1.0, panel);
+        }
+        for (i, &point) in outline.iter().enumerate() {
+            let next = outline[(i + 1) % outline.len()];
+            draw_line(point.x, point.y, next.x, next.y, 2.0, trim);
+        }
+
+        let cockpit = to_screen(self.cockpit());
+        draw_circle(cockpit.x, cockpit.y, size * 0.12, Color::new(0.2, 0.45, 0.8, 1.0));
+
+        for &engine in self.engines() {
+            let nozzle = to_screen(engine);
+            let color = if thrust { Color::new(0.6, 0.85, 1.0, 1.0) } else { DARKGRAY };
+            draw_circle(nozzle.x, nozzle.y, size * 0.1, color);
+        }
+    }
+}
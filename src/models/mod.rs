@@ -1,9 +1,37 @@
 mod star;
+mod orbit;
 mod planet;
+mod moon;
+mod station;
+mod asteroid;
+mod equipment;
+mod bounty_hunter;
+mod autopilot;
+mod fleet;
+mod dock;
+mod faction;
 mod solar_system;
+mod starfield;
+mod shading;
+mod particles;
+mod hull_style;
+mod trail;
 pub mod spaceship;
 
 pub use star::{Star, StarType};
 pub use planet::Planet;
+pub use moon::Moon;
+pub use station::Station;
+pub use asteroid::AsteroidBelt;
+pub use equipment::{MiningLaser, SmugglerCompartment};
+pub use bounty_hunter::{BountyHunter, HUNTER_DAMAGE};
+pub use dock::{DockId, Dockable};
+pub use faction::Faction;
 pub use solar_system::SolarSystem;
+pub use starfield::Starfield;
+pub use particles::Particles;
+pub use hull_style::HullStyle;
+pub use trail::Trail;
 pub use spaceship::Spaceship;
+pub use autopilot::Autopilot;
+pub use fleet::Fleet;
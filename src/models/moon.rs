@@ -0,0 +1,53 @@
+use macroquad::prelude::*;
+use super::orbit::Orbit;
+use super::shading::draw_night_side;
+use super::station::Station;
+use crate::economy::{Market, ProductType};
+use crate::rules::ProductPrices;
+
+pub struct Moon {
+    pub name: String,
+    pub orbit: Orbit,
+    pub radius: f32,
+    pub color: Color,
+    pub market: Market,
+    pub stations: Vec<Station>,
+}
+
+impl Moon {
+    pub fn new(name: String, orbit: Orbit, radius: f32, color: Color, product: ProductType, prices: ProductPrices) -> Self {
+        Self {
+            name,
+            orbit,
+            radius,
+            color,
+            market: Market::new(Some(product), prices),
+            stations: Vec::new(),
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.orbit.update(dt);
+        self.market.update(dt);
+        for station in &mut self.stations {
+            station.update(dt);
+        }
+    }
+
+    pub fn position(&self, parent: Vec2) -> Vec2 {
+        self.orbit.position(parent)
+    }
+
+    pub fn draw(&self, parent: Vec2, star: Vec2, camera_offset: Vec2) {
+        let pos = self.position(parent);
+        let screen_pos = pos - camera_offset;
+
+        draw_circle(screen_pos.x, screen_pos.y, self.radius, self.color);
+        draw_night_side(screen_pos, self.radius, star - camera_offset);
+        self.orbit.draw(parent, camera_offset, Color::new(0.3, 0.3, 0.3, 0.3));
+
+        for station in &self.stations {
+            station.draw(pos, camera_offset);
+        }
+    }
+}
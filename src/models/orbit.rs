@@ -0,0 +1,109 @@
+use macroquad::prelude::*;
+
+// Segments used when drawing an orbit path
+const ORBIT_DRAW_SEGMENTS: usize = 96;
+
+// Keplerian orbit around a parent body. The parent's position is passed in,
+// so orbits can be nested (moons around planets, stations around either).
+//
+// `mean_anomaly` advances at a constant rate; the actual position comes from
+// solving Kepler's equation, so bodies speed up near periapsis and slow down
+// near apoapsis (Kepler's second law). An eccentricity of 0 is a circle.
+#[derive(Clone, Copy)]
+pub struct Orbit {
+    pub mean_anomaly: f32,
+    pub radius: f32,           // Semi-major axis
+    pub speed: f32,            // Mean motion in radians per second
+    pub eccentricity: f32,     // 0 = circle, must stay below 1
+    pub periapsis_angle: f32,  // Direction of closest approach
+    pub inclination: f32,      // Tilt out of the view plane, seen as a squashed orbit
+}
+
+impl Orbit {
+    pub fn new(radius: f32, speed: f32, initial_angle: f32) -> Self {
+        Self::elliptical(radius, speed, initial_angle, 0.0, 0.0, 0.0)
+    }
+
+    pub fn elliptical(
+        radius: f32,
+        speed: f32,
+        initial_angle: f32,
+        eccentricity: f32,
+        periapsis_angle: f32,
+        inclination: f32,
+    ) -> Self {
+        Self {
+            mean_anomaly: initial_angle,
+            radius,
+            speed,
+            eccentricity: eccentricity.clamp(0.0, 0.95),
+            periapsis_angle,
+            inclination,
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.mean_anomaly = (self.mean_anomaly + self.speed * dt).rem_euclid(std::f32::consts::TAU);
+    }
+
+    // The same orbit `seconds` from now, used to lead moving targets
+    pub fn predicted(&self, seconds: f32) -> Orbit {
+        let mut future = *self;
+        future.update(seconds);
+        future
+    }
+
+    // Solve Kepler's equation M = E - e*sin(E) for the eccentric anomaly E
+    fn eccentric_anomaly(&self) -> f32 {
+        let e = self.eccentricity;
+        let m = self.mean_anomaly;
+        let mut ecc = if e < 0.8 { m } else { std::f32::consts::PI };
+        for _ in 0..8 {
+            let delta = (ecc - e * ecc.sin() - m) / (1.0 - e * ecc.cos());
+            ecc -= delta;
+            if delta.abs() < 1e-5 {
+                break;
+            }
+        }
+        ecc
+    }
+
+    // Offset from the parent for a given eccentric anomaly, with the parent at one focus
+    fn offset_at(&self, eccentric_anomaly: f32) -> Vec2 {
+        let e = self.eccentricity;
+        let semi_minor = self.radius * (1.0 - e * e).sqrt();
+        let in_plane = Vec2::new(
+            self.radius * (eccentric_anomaly.cos() - e),
+            semi_minor * eccentric_anomaly.sin() * self.inclination.cos(),
+        );
+        Vec2::from_angle(self.periapsis_angle).rotate(in_plane)
+    }
+
+    pub fn position(&self, parent: Vec2) -> Vec2 {
+        parent + self.offset_at(self.eccentric_anomaly())
+    }
+
+    // Angle of travel around the parent, useful for orienting things on the orbit
+    pub fn angle(&self) -> f32 {
+        let offset = self.offset_at(self.eccentric_anomaly());
+        offset.y.atan2(offset.x)
+    }
+
+    pub fn draw(&self, parent: Vec2, camera_offset: Vec2, color: Color) {
+        let screen_parent = parent - camera_offset;
+
+        if self.eccentricity == 0.0 && self.inclination == 0.0 {
+            draw_circle_lines(screen_parent.x, screen_parent.y, self.radius, 1.0, color);
+            return;
+        }
+
+        // Trace the ellipse by stepping the eccentric anomaly
+        let mut previous = screen_parent + self.offset_at(0.0);
+        for i in 1..=ORBIT_DRAW_SEGMENTS {
+            let ecc = i as f32 / ORBIT_DRAW_SEGMENTS as f32 * std::f32::consts::TAU;
+            let point = screen_parent + self.offset_at(ecc);
+            draw_line(previous.x, previous.y, point.x, point.y, 1.0, color);
+            previous = point;
+        }
+    }
+}
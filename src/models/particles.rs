@@ -0,0 +1,117 @@
+use std::collections::VecDeque;
+use macroquad::prelude::*;
+
+const MAX_PARTICLES: usize = 400; // Per emitter; the oldest make way past this
+const EXHAUST_LIFE: f32 = 0.45;    // Seconds
+const EXHAUST_SPEED: f32 = 90.0;
+const SPARK_LIFE: f32 = 0.6;
+const SPARK_SPEED: f32 = 140.0;
+const DUST_LIFE: f32 = 1.1;
+const DUST_SPEED: f32 = 35.0;
+
+struct Particle {
+    position: Vec2,
+    velocity: Vec2,
+    age: f32,
+    life: f32,
+    size: f32,
+    drag: f32, // Fraction of speed lost per second
+    start_color: Color,
+    end_color: Color,
+}
+
+// Short-lived specks in world space: engine exhaust, sparks and dust. They
+// stay where they were emitted, so a ship leaves its exhaust behind it.
+pub struct Particles {
+    particles: VecDeque<Particle>,
+}
+
+impl Particles {
+    pub fn new() -> Self {
+        Self { particles: VecDeque::new() }
+    }
+
+    // A puff from an engine nozzle at `position`, blown out along `direction`
+    pub fn emit_exhaust(&mut self, position: Vec2, direction: Vec2, size: f32) {
+        let spread = rand::gen_range(-0.35, 0.35);
+        let velocity = Vec2::from_angle(spread).rotate(direction) * EXHAUST_SPEED * rand::gen_range(0.6, 1.0);
+        self.push(Particle {
+            position,
+            velocity,
+            age: 0.0,
+            life: EXHAUST_LIFE * rand::gen_range(0.6, 1.0),
+            size: size * rand::gen_range(0.6, 1.0),
+            drag: 2.5,
+            start_color: Color::new(0.7, 0.9, 1.0, 0.9),
+            end_color: Color::new(1.0, 0.35, 0.1, 0.0),
+        });
+    }
+
+    // Bright fragments flying off in every direction
+    pub fn emit_sparks(&mut self, position: Vec2, count: usize) {
+        for _ in 0..count {
+            let velocity = Vec2::from_angle(rand::gen_range(0.0, std::f32::consts::TAU)) * SPARK_SPEED * rand::gen_range(0.3, 1.0);
+            self.push(Particle {
+                position,
+                velocity,
+                age: 0.0,
+                life: SPARK_LIFE * rand::gen_range(0.5, 1.0),
+                size: rand::gen_range(1.0, 2.2),
+                drag: 1.5,
+                start_color: Color::new(1.0, 0.95, 0.6, 1.0),
+                end_color: Color::new(1.0, 0.4, 0.0, 0.0),
+            });
+        }
+    }
+
+    // A ring of dust kicked up from a surface, spreading along it away from `normal`'s axis
+    pub fn emit_dust(&mut self, position: Vec2, normal: Vec2, count: usize) {
+        for _ in 0..count {
+            // Mostly sideways along the surface, a little upwards
+            let side = if rand::gen_range(0.0, 1.0) < 0.5 { normal.perp() } else { -normal.perp() };
+            let direction = (side + normal * rand::gen_range(0.0, 0.6)).normalize_or(normal);
+            self.push(Particle {
+                position,
+                velocity: direction * DUST_SPEED * rand::gen_range(0.4, 1.0),
+                age: 0.0,
+                life: DUST_LIFE * rand::gen_range(0.6, 1.0),
+                size: rand::gen_range(1.5, 3.5),
+                drag: 2.0,
+                start_color: Color::new(0.75, 0.7, 0.6, 0.6),
+                end_color: Color::new(0.5, 0.45, 0.4, 0.0),
+            });
+        }
+    }
+
+    fn push(&mut self, particle: Particle) {
+        if self.particles.len() >= MAX_PARTICLES {
+            self.particles.pop_front();
+        }
+        self.particles.push_back(particle);
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for particle in &mut self.particles {
+            particle.age += dt;
+            particle.position += particle.velocity * dt;
+            particle.velocity *= (1.0 - particle.drag * dt).max(0.0);
+        }
+        self.particles.retain(|particle| particle.age < particle.life);
+    }
+
+    pub fn draw(&self, camera_offset: Vec2) {
+        for particle in &self.particles {
+            let t = particle.age / particle.life;
+            let start = particle.start_color;
+            let end = particle.end_color;
+            let color = Color::new(
+                start.r + (end.r - start.r) * t,
+                start.g + (end.g - start.g) * t,
+                start.b + (end.b - start.b) * t,
+                start.a + (end.a - start.a) * t,
+            );
+            let pos = particle.position - camera_offset;
+            draw_circle(pos.x, pos.y, particle.size * (1.0 - t * 0.5), color);
+        }
+    }
+}
@@ -1,46 +1,72 @@
 use macroquad::prelude::*;
-use crate::economy::ProductType;
+use super::orbit::Orbit;
+use super::shading::draw_night_side;
+use super::moon::Moon;
+use super::station::Station;
+use super::trail::Trail;
+use crate::economy::{Market, ProductType};
+use crate::rules::ProductPrices;
+
+const TRAIL_INTERVAL: f32 = 0.5; // Planets are slow, so their trails sample sparsely
 
 pub struct Planet {
-    pub angle: f32,
-    pub orbital_radius: f32,
-    pub orbital_speed: f32,
+    pub name: String,
+    pub orbit: Orbit,
     pub radius: f32,
     pub color: Color,
-    pub product: ProductType,
+    pub market: Market,
+    pub moons: Vec<Moon>,
+    pub stations: Vec<Station>,
+    pub faction: Option<usize>, // Index into SolarSystem::factions, None for independents
+    pub trail: Trail,
 }
 
 impl Planet {
-    pub fn new(orbital_radius: f32, orbital_speed: f32, radius: f32, color: Color, initial_angle: f32, product: ProductType) -> Self {
+    pub fn new(name: String, orbit: Orbit, radius: f32, color: Color, product: ProductType, prices: ProductPrices) -> Self {
         Self {
-            angle: initial_angle,
-            orbital_radius,
-            orbital_speed,
+            name,
+            orbit,
             radius,
             color,
-            product,
+            market: Market::new(Some(product), prices),
+            moons: Vec::new(),
+            stations: Vec::new(),
+            faction: None,
+            trail: Trail::new(TRAIL_INTERVAL),
         }
     }
 
     pub fn update(&mut self, dt: f32) {
-        self.angle += self.orbital_speed * dt;
+        self.orbit.update(dt);
+        self.market.update(dt);
+        for moon in &mut self.moons {
+            moon.update(dt);
+        }
+        for station in &mut self.stations {
+            station.update(dt);
+        }
     }
 
     pub fn position(&self, center: Vec2) -> Vec2 {
-        Vec2::new(
-            center.x + self.orbital_radius * self.angle.cos(),
-            center.y + self.orbital_radius * self.angle.sin(),
-        )
+        self.orbit.position(center)
     }
 
     pub fn draw(&self, center: Vec2, camera_offset: Vec2) {
         let pos = self.position(center);
         let screen_pos = pos - camera_offset;
-        let screen_center = center - camera_offset;
 
         draw_circle(screen_pos.x, screen_pos.y, self.radius, self.color);
+        // Lit from the star at the centre of its orbit
+        draw_night_side(screen_pos, self.radius, center - camera_offset);
 
         // Draw orbit path
-        draw_circle_lines(screen_center.x, screen_center.y, self.orbital_radius, 1.0, Color::new(0.3, 0.3, 0.3, 0.5));
+        self.orbit.draw(center, camera_offset, Color::new(0.3, 0.3, 0.3, 0.5));
+
+        for moon in &self.moons {
+            moon.draw(pos, center, camera_offset);
+        }
+        for station in &self.stations {
+            station.draw(pos, camera_offset);
+        }
     }
 }
@@ -0,0 +1,34 @@
+use macroquad::prelude::*;
+
+const SHADOW_STEPS: usize = 20; // Points along the arc of each shadow band
+// Where each band of the night side starts, in radii past the centre towards
+// the dark side, and how much it darkens. Overlapping bands soften the terminator.
+const SHADOW_BANDS: [(f32, f32); 3] = [(-0.2, 0.18), (0.0, 0.3), (0.3, 0.25)];
+
+// Darkens the half of a body facing away from the light. `pos` and `light`
+// are in the same space, the body's centre and what it's lit by.
+pub fn draw_night_side(pos: Vec2, radius: f32, light: Vec2) {
+    let away = (pos - light).normalize_or_zero();
+    if away == Vec2::ZERO {
+        return;
+    }
+    let facing = away.to_angle();
+    for (offset, alpha) in SHADOW_BANDS {
+        draw_circle_segment(pos, radius, facing, offset, Color::new(0.0, 0.0, 0.02, alpha));
+    }
+}
+
+// The part of a circle beyond a chord `offset` radii from the centre, on the side `facing` points to
+fn draw_circle_segment(center: Vec2, radius: f32, facing: f32, offset: f32, color: Color) {
+    let half_arc = offset.clamp(-1.0, 1.0).acos();
+    let points: Vec<Vec2> = (0..=SHADOW_STEPS)
+        .map(|i| {
+            let angle = facing - half_arc + 2.0 * half_arc * i as f32 / SHADOW_STEPS as f32;
+            center + Vec2::from_angle(angle) * radius
+        })
+        .collect();
+    // The segment is convex, so a fan from its first point covers it
+    for pair in points[1..].windows(2) {
+        draw_triangle(points[0], pair[0], pair[1], color);
+    }
+}
@@ -1,17 +1,41 @@
 use macroquad::prelude::*;
-use super::{Star, Planet};
+use super::{Star, Planet, Moon, Station, AsteroidBelt, DockId, Dockable, Faction, Starfield};
+use super::orbit::Orbit;
 use super::star::StarType;
-use crate::economy::ProductType;
+use crate::rules::ProductPrices;
+use crate::economy::{scale_price, ProductType, TradeSide, TradeTerms, CONTRABAND_PREMIUM, PRICE_SAMPLE_INTERVAL};
+
+const PLANET_NAMES: [&str; 9] = [
+    "Aurelia", "Borea", "Caldor", "Dravos", "Eos", "Fenrir", "Galt", "Helion", "Ixia",
+];
+const MOON_SUFFIXES: [&str; 3] = ["I", "II", "III"];
+const FACTION_NAMES: [&str; 4] = [
+    "Solar Union", "Free Traders Guild", "Helios Combine", "Outer Rim Syndicate",
+];
+const INDEPENDENT_CHANCE: f32 = 0.15;
+
+// Clearance kept between a belt and the planets (and their moons) on either side
+const BELT_CLEARANCE: f32 = 150.0;
+const MIN_BELT_WIDTH: f32 = 60.0;
+const MAX_BELTS: usize = 2;
 
 pub struct SolarSystem {
     pub star: Star,
     pub planets: Vec<Planet>,
+    pub belts: Vec<AsteroidBelt>,
+    pub factions: Vec<Faction>,
+    pub starfield: Starfield, // The sky behind the system
+    pub seed: u64,            // Everything above is generated from it
+    price_sample_timer: f32,
 }
 
 impl SolarSystem {
-    pub fn new(center: Vec2, star_type: StarType, planet_count: usize) -> Self {
+    pub fn new(center: Vec2, star_type: StarType, planet_count: usize, prices: ProductPrices, seed: u64) -> Self {
+        // The same seed always builds the same system
+        rand::srand(seed);
+
         // First generate planets to find the biggest one
-        let (planets, max_planet_radius) = Self::generate_random_planets(planet_count);
+        let (mut planets, max_planet_radius) = Self::generate_random_planets(planet_count, prices);
 
         // Ensure star is at least 3x the biggest planet
         let min_star_radius = max_planet_radius * 3.0;
@@ -20,10 +44,51 @@ impl SolarSystem {
             star.radius = min_star_radius;
         }
 
-        Self { star, planets }
+        let belts = Self::generate_asteroid_belts(&planets);
+        let factions = Self::generate_factions(&mut planets);
+
+        let starfield = Starfield::new(seed, star_type);
+
+        let mut system = Self { star, planets, belts, factions, starfield, seed, price_sample_timer: 0.0 };
+        system.record_prices();
+        system
+    }
+
+    // Place belts in the widest gaps between neighbouring orbits, or past the
+    // outermost planet when the system is too crowded
+    fn generate_asteroid_belts(planets: &[Planet]) -> Vec<AsteroidBelt> {
+        let mut gaps: Vec<(f32, f32)> = planets
+            .windows(2)
+            .map(|pair| {
+                let inner = &pair[0].orbit;
+                let outer = &pair[1].orbit;
+                (
+                    inner.radius * (1.0 + inner.eccentricity) + BELT_CLEARANCE,
+                    outer.radius * (1.0 - outer.eccentricity) - BELT_CLEARANCE,
+                )
+            })
+            .filter(|(inner, outer)| outer - inner >= MIN_BELT_WIDTH)
+            .collect();
+        gaps.sort_by(|a, b| (b.1 - b.0).total_cmp(&(a.1 - a.0)));
+        gaps.truncate(rand::gen_range(1, MAX_BELTS + 1));
+
+        if gaps.is_empty()
+            && let Some(last) = planets.last()
+        {
+            let inner = last.orbit.radius * (1.0 + last.orbit.eccentricity) + BELT_CLEARANCE;
+            gaps.push((inner, inner + 150.0));
+        }
+
+        gaps.into_iter()
+            .map(|(inner, outer)| {
+                // Keep density roughly constant regardless of belt size
+                let count = ((outer * outer - inner * inner) / 6000.0) as usize;
+                AsteroidBelt::new(inner, outer, count.clamp(40, 160))
+            })
+            .collect()
     }
 
-    fn generate_random_planets(count: usize) -> (Vec<Planet>, f32) {
+    fn generate_random_planets(count: usize, prices: ProductPrices) -> (Vec<Planet>, f32) {
         let mut planets = Vec::new();
         let mut max_planet_radius: f32 = 0.0;
 
@@ -50,44 +115,337 @@ impl SolarSystem {
             let radius = rand::gen_range(8.0, 35.0);
             max_planet_radius = max_planet_radius.max(radius);
 
-            // Random color
-            let color = Color::new(
-                rand::gen_range(0.3, 1.0),
-                rand::gen_range(0.3, 1.0),
-                rand::gen_range(0.3, 1.0),
-                1.0,
-            );
-
             // Random initial angle
             let initial_angle = rand::gen_range(0.0, std::f32::consts::TAU);
 
+            // Mildly elliptical, tilted orbits. Eccentricity is capped so the
+            // orbit never swings further than a third of the spacing to its neighbours.
+            let max_eccentricity = (spacing * 0.3 / orbital_radius).min(0.25);
+            let eccentricity = rand::gen_range(0.0, max_eccentricity);
+            let periapsis_angle = rand::gen_range(0.0, std::f32::consts::TAU);
+            let inclination = rand::gen_range(0.0, 0.35);
+
             // Assign product type cyclically to ensure variety
-            let products = ProductType::all();
+            let products = ProductType::raw_goods();
             let product = products[i % products.len()];
 
-            planets.push(Planet::new(
-                orbital_radius,
-                orbital_speed,
+            let name = PLANET_NAMES[i % PLANET_NAMES.len()].to_string();
+            let mut planet = Planet::new(
+                name,
+                Orbit::elliptical(orbital_radius, orbital_speed, initial_angle, eccentricity, periapsis_angle, inclination),
                 radius,
-                color,
-                initial_angle,
+                Self::random_color(),
                 product,
-            ));
+                prices,
+            );
+
+            // Bigger planets can hold on to moons, keep them well inside the orbit spacing
+            let moon_count = if radius > 20.0 { rand::gen_range(0, 3) } else { 0 };
+            let mut moon_orbit_radius = radius + 25.0;
+            for suffix in MOON_SUFFIXES.iter().take(moon_count) {
+                let moon_radius = rand::gen_range(3.0, radius * 0.3);
+                moon_orbit_radius += moon_radius + rand::gen_range(8.0, 18.0);
+                let moon_product = products[rand::gen_range(0, products.len())];
+                let mut moon = Moon::new(
+                    format!("{} {}", planet.name, suffix),
+                    Orbit::elliptical(
+                        moon_orbit_radius,
+                        rand::gen_range(0.8, 1.6),
+                        rand::gen_range(0.0, std::f32::consts::TAU),
+                        rand::gen_range(0.0, 0.1),
+                        rand::gen_range(0.0, std::f32::consts::TAU),
+                        rand::gen_range(0.0, 0.5),
+                    ),
+                    moon_radius,
+                    Self::random_color(),
+                    moon_product,
+                    prices,
+                );
+                moon_orbit_radius += moon_radius;
+
+                // Occasionally a moon gets its own station
+                if rand::gen_range(0.0, 1.0) < 0.3 {
+                    moon.stations.push(Station::new(
+                        format!("{} Outpost", moon.name),
+                        Orbit::new(moon_radius + 10.0, 2.5, rand::gen_range(0.0, std::f32::consts::TAU)),
+                        4.0,
+                        prices,
+                    ));
+                }
+                planet.moons.push(moon);
+            }
+
+            // Roughly half the planets have an orbital trade station
+            if rand::gen_range(0.0, 1.0) < 0.5 {
+                planet.stations.push(Station::new(
+                    format!("{} Station", planet.name),
+                    Orbit::new(radius + 14.0, 1.2, rand::gen_range(0.0, std::f32::consts::TAU)),
+                    6.0,
+                    prices,
+                ));
+            }
+
+            planets.push(planet);
         }
 
         (planets, max_planet_radius)
     }
 
+    // Split the planets into neighbouring runs of orbits, one per faction,
+    // leaving the odd world independent
+    fn generate_factions(planets: &mut [Planet]) -> Vec<Faction> {
+        let count = (planets.len() / 2).clamp(1, FACTION_NAMES.len());
+        let mut factions: Vec<Faction> = FACTION_NAMES
+            .iter()
+            .take(count)
+            .map(|name| Faction::new(name.to_string(), Self::random_color()))
+            .collect();
+
+        let per_faction = planets.len().div_ceil(count);
+        for (i, planet) in planets.iter_mut().enumerate() {
+            if rand::gen_range(0.0, 1.0) >= INDEPENDENT_CHANCE {
+                planet.faction = Some(i / per_faction);
+            }
+        }
+
+        // Each faction outlaws a product or two that none of its own worlds make
+        for (f, faction) in factions.iter_mut().enumerate() {
+            let produced: Vec<ProductType> = planets
+                .iter()
+                .filter(|planet| planet.faction == Some(f))
+                .flat_map(|planet| {
+                    std::iter::once(planet.market.produces).chain(planet.moons.iter().map(|moon| moon.market.produces))
+                })
+                .flatten()
+                .collect();
+            let mut candidates: Vec<ProductType> =
+                ProductType::all().into_iter().filter(|p| !produced.contains(p)).collect();
+            let banned = if rand::gen_range(0.0, 1.0) < 0.3 { 2 } else { 1 };
+            for _ in 0..banned.min(candidates.len()) {
+                faction.contraband.push(candidates.swap_remove(rand::gen_range(0, candidates.len())));
+            }
+        }
+        factions
+    }
+
+    fn random_color() -> Color {
+        Color::new(
+            rand::gen_range(0.3, 1.0),
+            rand::gen_range(0.3, 1.0),
+            rand::gen_range(0.3, 1.0),
+            1.0,
+        )
+    }
+
+    // Moves every body along its orbit without running the markets, to bring
+    // a freshly generated system up to a saved game's time
+    pub fn advance_orbits(&mut self, seconds: f32) {
+        for planet in &mut self.planets {
+            planet.orbit.update(seconds);
+            for moon in &mut planet.moons {
+                moon.orbit.update(seconds);
+                for station in &mut moon.stations {
+                    station.orbit.update(seconds);
+                }
+            }
+            for station in &mut planet.stations {
+                station.orbit.update(seconds);
+            }
+        }
+    }
+
+    // Every landable body in the system, parents before children
+    pub fn dock_ids(&self) -> Vec<DockId> {
+        let mut ids = Vec::new();
+        for (p, planet) in self.planets.iter().enumerate() {
+            ids.push(DockId::Planet(p));
+            for s in 0..planet.stations.len() {
+                ids.push(DockId::PlanetStation(p, s));
+            }
+            for (m, moon) in planet.moons.iter().enumerate() {
+                ids.push(DockId::Moon(p, m));
+                for s in 0..moon.stations.len() {
+                    ids.push(DockId::MoonStation(p, m, s));
+                }
+            }
+        }
+        ids
+    }
+
+    pub fn dockable(&self, id: DockId) -> Option<&dyn Dockable> {
+        let planet = self.planets.get(id.planet_index())?;
+        Some(match id {
+            DockId::Planet(_) => planet as &dyn Dockable,
+            DockId::Moon(_, m) => planet.moons.get(m)? as &dyn Dockable,
+            DockId::PlanetStation(_, s) => planet.stations.get(s)? as &dyn Dockable,
+            DockId::MoonStation(_, m, s) => planet.moons.get(m)?.stations.get(s)? as &dyn Dockable,
+        })
+    }
+
+    pub fn dockable_mut(&mut self, id: DockId) -> Option<&mut dyn Dockable> {
+        let planet = self.planets.get_mut(id.planet_index())?;
+        Some(match id {
+            DockId::Planet(_) => planet as &mut dyn Dockable,
+            DockId::Moon(_, m) => planet.moons.get_mut(m)? as &mut dyn Dockable,
+            DockId::PlanetStation(_, s) => planet.stations.get_mut(s)? as &mut dyn Dockable,
+            DockId::MoonStation(_, m, s) => planet.moons.get_mut(m)?.stations.get_mut(s)? as &mut dyn Dockable,
+        })
+    }
+
+    // World position of a body, composed from its parents' positions
+    pub fn dock_position(&self, id: DockId) -> Option<Vec2> {
+        self.predict_dock_position(id, 0.0)
+    }
+
+    // Where a body will be `seconds` from now, following its whole orbit chain
+    pub fn predict_dock_position(&self, id: DockId, seconds: f32) -> Option<Vec2> {
+        let planet = self.planets.get(id.planet_index())?;
+        let planet_pos = planet.orbit.predicted(seconds).position(self.star.position);
+        Some(match id {
+            DockId::Planet(_) => planet_pos,
+            DockId::Moon(_, m) => planet.moons.get(m)?.orbit.predicted(seconds).position(planet_pos),
+            DockId::PlanetStation(_, s) => planet.stations.get(s)?.orbit.predicted(seconds).position(planet_pos),
+            DockId::MoonStation(_, m, s) => {
+                let moon = planet.moons.get(m)?;
+                let moon_pos = moon.orbit.predicted(seconds).position(planet_pos);
+                moon.stations.get(s)?.orbit.predicted(seconds).position(moon_pos)
+            }
+        })
+    }
+
+    // Where to fly to meet a moving body at `speed`, and how long it takes to
+    // get there. The flight time is refined a few times against its orbit.
+    pub fn intercept(&self, from: Vec2, speed: f32, id: DockId) -> Option<(Vec2, f32)> {
+        let speed = speed.max(1.0);
+        let mut aim = self.dock_position(id)?;
+        let mut flight_time = 0.0;
+        for _ in 0..5 {
+            flight_time = (aim - from).length() / speed;
+            aim = self.predict_dock_position(id, flight_time)?;
+        }
+        Some((aim, flight_time))
+    }
+
+    // Moons and stations answer to whoever controls their planet
+    pub fn faction_of(&self, id: DockId) -> Option<&Faction> {
+        let faction = self.planets.get(id.planet_index())?.faction?;
+        self.factions.get(faction)
+    }
+
+    pub fn faction_of_mut(&mut self, id: DockId) -> Option<&mut Faction> {
+        let faction = self.planets.get(id.planet_index())?.faction?;
+        self.factions.get_mut(faction)
+    }
+
+    pub fn is_contraband(&self, id: DockId, product: ProductType) -> bool {
+        self.faction_of(id).is_some_and(|faction| faction.is_contraband(product))
+    }
+
+    pub fn landing_allowed(&self, id: DockId) -> bool {
+        self.faction_of(id).is_none_or(|faction| faction.allows_landing())
+    }
+
+    pub fn adjust_reputation(&mut self, id: DockId, delta: f32) {
+        if let Some(faction) = self.faction_of_mut(id) {
+            faction.adjust_reputation(delta);
+        }
+    }
+
+    // How the player is treated trading a product here. Contraband only
+    // changes hands on the black market, at a premium and off the books.
+    pub fn trade_terms(&self, id: DockId, product: ProductType, side: TradeSide) -> TradeTerms {
+        if self.is_contraband(id, product) {
+            return TradeTerms { price_factor: CONTRABAND_PREMIUM, tariff_factor: 0.0, black_market: true };
+        }
+        match self.faction_of(id) {
+            Some(faction) => TradeTerms {
+                price_factor: match side {
+                    TradeSide::Buy => faction.buy_price_factor(),
+                    TradeSide::Sell => faction.sell_price_factor(),
+                },
+                tariff_factor: faction.tariff_factor(),
+                black_market: false,
+            },
+            None => TradeTerms::standard(),
+        }
+    }
+
+    pub fn docking_fee(&self, id: DockId) -> i32 {
+        let Some(body) = self.dockable(id) else { return 0 };
+        let factor = self.faction_of(id).map(|f| f.tariff_factor()).unwrap_or(1.0);
+        scale_price(body.market().taxes.docking_fee, factor)
+    }
+
+    // Closest asteroid whose surface is within `range` of a point, as (belt, asteroid, distance)
+    pub fn nearest_asteroid(&self, point: Vec2, range: f32) -> Option<(usize, usize, f32)> {
+        let mut nearest: Option<(usize, usize, f32)> = None;
+        for (b, belt) in self.belts.iter().enumerate() {
+            for (a, asteroid) in belt.asteroids.iter().enumerate() {
+                let distance = (asteroid.position(self.star.position) - point).length() - asteroid.radius;
+                if distance < range && nearest.is_none_or(|(_, _, d)| distance < d) {
+                    nearest = Some((b, a, distance));
+                }
+            }
+        }
+        nearest
+    }
+
     pub fn update(&mut self, dt: f32) {
         for planet in &mut self.planets {
             planet.update(dt);
+            let pos = planet.position(self.star.position);
+            planet.trail.record(dt, pos);
+        }
+        for belt in &mut self.belts {
+            belt.update(dt);
+        }
+        for faction in &mut self.factions {
+            faction.update(dt);
+        }
+
+        self.price_sample_timer += dt;
+        if self.price_sample_timer >= PRICE_SAMPLE_INTERVAL {
+            self.price_sample_timer = 0.0;
+            self.record_prices();
         }
     }
 
+    fn record_prices(&mut self) {
+        for dock in self.dock_ids() {
+            if let Some(body) = self.dockable_mut(dock) {
+                body.market_mut().record_prices();
+            }
+        }
+    }
+
+    // Average quote for a product across the planets' markets, the ones the
+    // price index compares
+    pub fn average_price(&self, product: ProductType) -> f32 {
+        let total: i32 = self.planets.iter().map(|planet| planet.market.quote(product)).sum();
+        total as f32 / self.planets.len().max(1) as f32
+    }
+
     pub fn draw(&self, camera_offset: Vec2) {
         self.star.draw(camera_offset);
+        for belt in &self.belts {
+            belt.draw(self.star.position, camera_offset);
+        }
         for planet in &self.planets {
             planet.draw(self.star.position, camera_offset);
+
+            // Territory ring in the controlling faction's colour
+            if let Some(faction) = planet.faction.and_then(|f| self.factions.get(f)) {
+                let pos = planet.position(self.star.position) - camera_offset;
+                let color = Color::new(faction.color.r, faction.color.g, faction.color.b, 0.5);
+                draw_circle_lines(pos.x, pos.y, planet.radius + 5.0, 1.5, color);
+            }
+        }
+    }
+
+    pub fn draw_trails(&self, camera_offset: Vec2) {
+        for planet in &self.planets {
+            let color = Color::new(planet.color.r, planet.color.g, planet.color.b, 0.6);
+            planet.trail.draw(planet.position(self.star.position), camera_offset, color);
         }
     }
 }
@@ -1,6 +1,52 @@
 use macroquad::prelude::*;
-use super::Planet;
-use crate::economy::{Inventory, FUEL_CONSUMPTION_PER_SECOND, FOOD_CONSUMPTION_INTERVAL, FOOD_CONSUMED_PER_INTERVAL};
+use super::{SolarSystem, DockId, MiningLaser, SmugglerCompartment, Autopilot, HullStyle, Particles, Trail};
+use crate::input::{Action, Bindings};
+use crate::ledger::{Ledger, Transaction, TransactionKind};
+use crate::message_log::MessageLog;
+use crate::rules::GameRules;
+use crate::economy::{
+    Inventory, ProductType,
+    MINING_RANGE, MINING_TIME, RARE_ORE_CHANCE, CARGO_CAPACITY, MAX_HULL, COLLISION_DAMAGE,
+    ASTEROID_COLLISION_DAMAGE, COLLISION_COOLDOWN, STAR_HEAT_RADIUS_FACTOR, STAR_HEAT_DAMAGE_PER_SECOND,
+    DAMAGE_DEGRADES_SHIP, INSURANCE_PAYOUT, REPUTATION_PER_TRADE_UNIT, CUSTOMS_SCAN_CHANCE,
+    COMPARTMENT_DETECTION_CHANCE, CONTRABAND_FINE_PER_UNIT, REPUTATION_PER_SMUGGLING_BUST, TradeService, TradeSide, TradeTerms,
+    LOW_FUEL_WARNING, LOW_FOOD_WARNING,
+};
+
+// How close to a body's surface the ship must be to land
+const DOCKING_RANGE: f32 = 20.0;
+const DOCKING_TIME: f32 = 1.2;       // Seconds to land or take off
+const LANDED_SCALE: f32 = 0.5;       // Size on the surface, relative to flying
+const LANDING_SWEEP: f32 = 0.6;      // Radians the landing arc curves round the body
+const TAKEOFF_MAX_TURN: f32 = 1.0;   // How far round from where it sat a ship can launch
+const EXHAUST_RATE: f32 = 40.0;      // Puffs per second from each nozzle at full thrust
+const SPARKS_PER_DAMAGE: f32 = 0.8;
+const DUST_PUFFS: usize = 18;
+const TRAIL_INTERVAL: f32 = 0.1;
+const PREDICTION_TIME: f32 = 8.0;    // Seconds of flight shown ahead of the ship
+const PREDICTION_STEPS: usize = 40;
+const HEADING_CONE: f32 = 0.2;       // Radians off the nose a body can be and still count as the target
+
+// A curved path onto or off a body, relative to the body's centre so it moves with it
+#[derive(Clone, Copy)]
+struct DockingArc {
+    start: Vec2,
+    control: Vec2,
+    end: Vec2,
+}
+
+impl DockingArc {
+    // Eased along a quadratic curve, so the ship slows as it settles
+    fn point(&self, t: f32) -> Vec2 {
+        let t = t * t * (3.0 - 2.0 * t);
+        self.start * (1.0 - t) * (1.0 - t) + self.control * 2.0 * (1.0 - t) * t + self.end * t * t
+    }
+
+    fn direction(&self, t: f32) -> Vec2 {
+        let t = t * t * (3.0 - 2.0 * t);
+        ((self.control - self.start) * (1.0 - t) + (self.end - self.control) * t).normalize_or(self.end - self.start)
+    }
+}
 
 #[derive(PartialEq, Clone, Copy)]
 pub enum SpaceshipState {
@@ -11,6 +57,7 @@ pub enum SpaceshipState {
 }
 
 pub struct Spaceship {
+    pub name: String,
     pub position: Vec2,
     pub velocity: Vec2,
     pub rotation: f32,
@@ -18,15 +65,33 @@ pub struct Spaceship {
     pub base_size: f32,
     pub speed: f32,
     pub state: SpaceshipState,
-    pub landed_planet_index: Option<usize>,
+    pub docked_at: Option<DockId>,
     pub animation_progress: f32,
     pub inventory: Inventory,
     pub food_timer: f32, // Timer for food consumption
+    pub mining_laser: Option<MiningLaser>,
+    pub mining: bool,                          // Mining key held this frame
+    pub mining_progress: f32,                  // Seconds spent on the current unit of ore
+    pub mining_target: Option<(usize, usize)>, // (belt, asteroid) currently being mined
+    pub hull: f32,
+    pub insured: bool,
+    pub destroyed: bool,
+    pub collision_cooldown: f32,
+    pub autopilot: Option<Autopilot>, // Flies the ship whenever the player isn't
+    pub smuggler_compartment: Option<SmugglerCompartment>,
+    pub hull_style: HullStyle,
+    pub surface_offset: Vec2,           // Where on its dock the ship sits, from the body's centre
+    docking_arc: Option<DockingArc>,    // While landing or taking off
+    particles: Particles,
+    pub trail: Trail,
+    low_fuel_warned: bool,              // Set once the low fuel warning is posted, until refuelled
+    low_food_warned: bool,
 }
 
 impl Spaceship {
-    pub fn new(position: Vec2) -> Self {
+    pub fn new(name: String, position: Vec2, inventory: Inventory) -> Self {
         Self {
+            name,
             position,
             velocity: Vec2::ZERO,
             rotation: 0.0,
@@ -34,75 +99,173 @@ impl Spaceship {
             base_size: 15.0,
             speed: 100.0,
             state: SpaceshipState::Flying,
-            landed_planet_index: None,
+            docked_at: None,
             animation_progress: 0.0,
-            inventory: Inventory::new(),
+            inventory,
             food_timer: 0.0,
+            mining_laser: None,
+            mining: false,
+            mining_progress: 0.0,
+            mining_target: None,
+            hull: MAX_HULL,
+            insured: false,
+            destroyed: false,
+            collision_cooldown: 0.0,
+            autopilot: None,
+            smuggler_compartment: None,
+            hull_style: HullStyle::Arrow,
+            surface_offset: Vec2::ZERO,
+            docking_arc: None,
+            particles: Particles::new(),
+            trail: Trail::new(TRAIL_INTERVAL),
+            low_fuel_warned: false,
+            low_food_warned: false,
         }
     }
 
-    pub fn handle_input(&mut self, planets: &[Planet], star_position: Vec2) {
-        match self.state {
-            SpaceshipState::Flying => {
-                let mut direction = Vec2::ZERO;
+    // Still able to fly and trade
+    pub fn is_operational(&self) -> bool {
+        !self.destroyed && self.inventory.food > 0
+    }
 
-                // WASD controls
-                if is_key_down(KeyCode::W) || is_key_down(KeyCode::Up) {
-                    direction.y -= 1.0;
-                }
-                if is_key_down(KeyCode::S) || is_key_down(KeyCode::Down) {
-                    direction.y += 1.0;
-                }
-                if is_key_down(KeyCode::A) || is_key_down(KeyCode::Left) {
-                    direction.x -= 1.0;
-                }
-                if is_key_down(KeyCode::D) || is_key_down(KeyCode::Right) {
-                    direction.x += 1.0;
-                }
+    // Swoops down onto the body, curving round it the way the ship was heading
+    pub fn begin_landing(&mut self, dock: DockId, system: &SolarSystem) {
+        let (Some(pos), Some(body)) = (system.dock_position(dock), system.dockable(dock)) else {
+            return;
+        };
+        let start = self.position - pos;
+        let sweep = if start.perp_dot(self.velocity) < 0.0 { -LANDING_SWEEP } else { LANDING_SWEEP };
+        self.docking_arc = Some(DockingArc {
+            start,
+            control: Vec2::from_angle(sweep * 0.5).rotate(start),
+            end: Vec2::from_angle(sweep).rotate(start.normalize_or(Vec2::X)) * body.radius(),
+        });
+        self.state = SpaceshipState::Landing;
+        self.docked_at = Some(dock);
+        self.animation_progress = 0.0;
+        self.velocity = Vec2::ZERO;
+    }
 
-                // Normalize direction to prevent faster diagonal movement
-                if direction.length() > 0.0 {
-                    direction = direction.normalize();
-                }
+    // Lifts straight off the surface, then turns away from whatever the body
+    // orbits so the ship doesn't launch into it
+    pub fn begin_takeoff(&mut self, system: &SolarSystem) {
+        let Some(dock) = self.docked_at else { return };
+        let (Some(pos), Some(body)) = (system.dock_position(dock), system.dockable(dock)) else {
+            return;
+        };
+        let parent_pos = match dock {
+            DockId::Planet(_) => Some(system.star.position),
+            DockId::Moon(p, _) | DockId::PlanetStation(p, _) => system.dock_position(DockId::Planet(p)),
+            DockId::MoonStation(p, m, _) => system.dock_position(DockId::Moon(p, m)),
+        };
+        let away = parent_pos.map(|parent| (pos - parent).normalize_or(Vec2::X)).unwrap_or(Vec2::X);
+        let up = self.surface_offset.normalize_or(away);
+        let turn = up.perp_dot(away).atan2(up.dot(away)).clamp(-TAKEOFF_MAX_TURN, TAKEOFF_MAX_TURN);
+        let clearance = body.radius() + self.base_size * 1.5;
+        self.docking_arc = Some(DockingArc {
+            start: self.surface_offset,
+            control: up * clearance,
+            end: Vec2::from_angle(turn).rotate(up) * clearance,
+        });
+        self.particles.emit_dust(self.position, up, DUST_PUFFS);
+        self.state = SpaceshipState::TakingOff;
+        self.animation_progress = 0.0;
+    }
 
-                self.velocity = direction * self.speed;
+    // Sets the ship down next to another one, as when a new ship is bought
+    pub fn dock_beside(&mut self, other: &Spaceship) {
+        self.state = SpaceshipState::Landed;
+        self.docked_at = other.docked_at;
+        self.surface_offset = other.surface_offset;
+        self.position = other.position;
+        self.rotation = other.rotation;
+        self.size = self.base_size * LANDED_SCALE;
+    }
 
-                // Update rotation to face movement direction
-                if direction.length() > 0.0 {
-                    self.rotation = direction.y.atan2(direction.x);
-                }
+    // Puts the ship straight down on a dock, as when a saved game is loaded.
+    // It's moved onto the body with its next update.
+    pub fn set_down(&mut self, dock: DockId, surface_offset: Vec2) {
+        self.state = SpaceshipState::Landed;
+        self.docked_at = Some(dock);
+        self.surface_offset = surface_offset;
+        self.size = self.base_size * LANDED_SCALE;
+    }
 
-                // Check for spacebar to initiate landing
-                if is_key_pressed(KeyCode::Space) {
-                    if let Some(planet_idx) = self.find_nearby_planet(planets, star_position) {
-                        self.state = SpaceshipState::Landing;
-                        self.landed_planet_index = Some(planet_idx);
-                        self.animation_progress = 0.0;
-                        self.velocity = Vec2::ZERO;
-                    }
-                }
-            }
-            SpaceshipState::Landed => {
-                // Check for spacebar to initiate takeoff
-                if is_key_pressed(KeyCode::Space) {
-                    self.state = SpaceshipState::TakingOff;
-                    self.animation_progress = 0.0;
-                }
-            }
-            _ => {}
+    // Let the autopilot (if any) steer and trade for this frame
+    pub fn run_autopilot(&mut self, dt: f32, system: &mut SolarSystem, ledger: &mut Ledger, rules: &GameRules) {
+        if let Some(mut autopilot) = self.autopilot.take() {
+            autopilot.drive(self, dt, system, ledger, rules);
+            self.autopilot = Some(autopilot);
+        } else if self.state == SpaceshipState::Flying {
+            self.velocity = Vec2::ZERO;
+            self.mining = false;
+        }
+    }
+
+    pub fn handle_input(&mut self, system: &SolarSystem, bindings: &Bindings) {
+        // Only flying is steered from here; taking off is the trade screen's job
+        if self.destroyed || self.state != SpaceshipState::Flying {
+            return;
+        }
+
+        let mut direction = Vec2::ZERO;
+
+        // WASD controls by default
+        if bindings.is_down(Action::ThrustUp) {
+            direction.y -= 1.0;
+        }
+        if bindings.is_down(Action::ThrustDown) {
+            direction.y += 1.0;
+        }
+        if bindings.is_down(Action::ThrustLeft) {
+            direction.x -= 1.0;
+        }
+        if bindings.is_down(Action::ThrustRight) {
+            direction.x += 1.0;
+        }
+
+        // Normalize direction to prevent faster diagonal movement
+        if direction.length() > 0.0 {
+            direction = direction.normalize();
+        }
+
+        self.velocity = direction * self.effective_speed();
+
+        // Hold the mine key (M) to fire the mining laser at the nearest asteroid
+        self.mining = bindings.is_down(Action::Mine);
+
+        // Update rotation to face movement direction
+        if direction.length() > 0.0 {
+            self.rotation = direction.y.atan2(direction.x);
         }
+
+        // Check for the land key to initiate landing
+        if bindings.is_pressed(Action::Land)
+            && let Some(dock) = self.find_nearby_dock(system)
+            && system.landing_allowed(dock)
+        {
+            self.begin_landing(dock, system);
+        }
+    }
+
+    // Kept apart from update so the fleet can run it for ships that no longer fly,
+    // letting the sparks of a destroyed ship burn out
+    pub fn update_particles(&mut self, dt: f32) {
+        self.particles.update(dt);
     }
 
-    pub fn update(&mut self, dt: f32, planets: &[Planet], star_position: Vec2) {
+    pub fn update(&mut self, dt: f32, system: &mut SolarSystem, ledger: &mut Ledger, log: &mut MessageLog, rules: &GameRules) {
+        if self.destroyed {
+            return;
+        }
+        self.trail.record(dt, self.position);
+
         // Food consumption timer
         self.food_timer += dt;
-        if self.food_timer >= FOOD_CONSUMPTION_INTERVAL {
+        if self.food_timer >= rules.food_consumption_interval {
             self.food_timer = 0.0;
-            match self.state {
-                SpaceshipState::Flying => {
-                    self.inventory.food = (self.inventory.food - FOOD_CONSUMED_PER_INTERVAL).max(0);
-                }
-                _ => {}
+            if self.state == SpaceshipState::Flying {
+                self.inventory.food = (self.inventory.food - rules.food_consumed_per_interval).max(0);
             }
         }
 
@@ -111,112 +274,517 @@ impl Spaceship {
                 // Only move if we have fuel
                 if self.inventory.fuel > 0.0 && self.velocity.length() > 0.0 {
                     self.position += self.velocity * dt;
+                    self.emit_exhaust(dt, 1.0);
 
                     // Consume fuel when moving
-                    let fuel_consumed = FUEL_CONSUMPTION_PER_SECOND * dt;
+                    let fuel_consumed = rules.fuel_consumption_per_second * dt;
                     self.inventory.fuel = (self.inventory.fuel - fuel_consumed).max(0.0);
                 } else if self.inventory.fuel == 0.0 {
                     // No fuel - can't move
                     self.velocity = Vec2::ZERO;
                 }
+
+                self.collide_with_bodies(dt, system);
+                self.collide_with_asteroids(system);
+                self.update_mining(dt, system);
             }
             SpaceshipState::Landing => {
-                // Animate size decrease
-                self.animation_progress += dt * 2.0; // 0.5 seconds to land
+                let was_halfway = self.animation_progress >= 0.5;
+                self.animation_progress = (self.animation_progress + dt / DOCKING_TIME).min(1.0);
+                self.follow_docking_arc(system);
+                self.size = self.base_size * (1.0 - self.animation_progress * (1.0 - LANDED_SCALE));
+                self.emit_exhaust(dt, 0.5);
+
+                // Customs get their look at the hold on the way down
+                if !was_halfway
+                    && self.animation_progress >= 0.5
+                    && let Some(dock) = self.docked_at
+                {
+                    self.customs_scan(dock, system, ledger, log);
+                }
                 if self.animation_progress >= 1.0 {
-                    self.animation_progress = 1.0;
                     self.state = SpaceshipState::Landed;
-
-                    // Auto-sell cargo when landing completes
-                    if let Some(planet_idx) = self.landed_planet_index {
-                        if let Some(planet) = planets.get(planet_idx) {
-                            self.inventory.sell_all_cargo(planet.product);
-                        }
+                    if let Some(arc) = self.docking_arc.take() {
+                        self.surface_offset = arc.end;
+                        self.particles.emit_dust(self.position, arc.end.normalize_or(Vec2::X), DUST_PUFFS);
                     }
-                }
-                self.size = self.base_size * (1.0 - self.animation_progress * 0.7); // Shrink to 30% size
 
-                // Follow planet during landing
-                if let Some(planet_idx) = self.landed_planet_index {
-                    if let Some(planet) = planets.get(planet_idx) {
-                        self.position = planet.position(star_position);
+                    // Pay to dock, then auto-sell cargo when landing completes
+                    if let Some(dock) = self.docked_at {
+                        self.pay_docking_fee(dock, system, ledger);
+                        self.sell_cargo(dock, system, ledger, log, rules);
                     }
                 }
             }
             SpaceshipState::Landed => {
-                // Follow planet position
-                if let Some(planet_idx) = self.landed_planet_index {
-                    if let Some(planet) = planets.get(planet_idx) {
-                        self.position = planet.position(star_position);
-                    }
-                }
+                // Follow the body's position
+                self.follow_dock(system);
             }
             SpaceshipState::TakingOff => {
-                // Animate size increase
-                self.animation_progress += dt * 2.0; // 0.5 seconds to take off
+                self.animation_progress = (self.animation_progress + dt / DOCKING_TIME).min(1.0);
+                self.follow_docking_arc(system);
+                self.size = self.base_size * (LANDED_SCALE + self.animation_progress * (1.0 - LANDED_SCALE));
+                self.emit_exhaust(dt, 1.0);
+
                 if self.animation_progress >= 1.0 {
-                    self.animation_progress = 1.0;
                     self.state = SpaceshipState::Flying;
-                    self.landed_planet_index = None;
+                    self.docked_at = None;
+                    self.docking_arc = None;
                 }
-                self.size = self.base_size * (0.3 + self.animation_progress * 0.7); // Grow back to full size
+            }
+        }
 
-                // Follow planet during takeoff
-                if let Some(planet_idx) = self.landed_planet_index {
-                    if let Some(planet) = planets.get(planet_idx) {
-                        self.position = planet.position(star_position);
-                    }
-                }
+        self.collision_cooldown = (self.collision_cooldown - dt).max(0.0);
+        if DAMAGE_DEGRADES_SHIP {
+            // A battered hull can't be loaded as heavily (down to 70% of capacity)
+            self.inventory.capacity = (CARGO_CAPACITY as f32 * (0.7 + 0.3 * self.hull_fraction())).round() as i32;
+        }
+        self.warn_low_supplies(log);
+        if self.hull <= 0.0 {
+            self.on_hull_breached(system, ledger);
+        }
+    }
+
+    // One warning per shortage; topping up re-arms it
+    fn warn_low_supplies(&mut self, log: &mut MessageLog) {
+        if self.inventory.fuel < LOW_FUEL_WARNING {
+            if !self.low_fuel_warned {
+                log.warning(format!("{} is low on fuel ({:.0} left)", self.name, self.inventory.fuel));
+                self.low_fuel_warned = true;
             }
+        } else {
+            self.low_fuel_warned = false;
+        }
+        if self.inventory.food < LOW_FOOD_WARNING {
+            if !self.low_food_warned {
+                log.warning(format!("{} is low on food ({} left)", self.name, self.inventory.food));
+                self.low_food_warned = true;
+            }
+        } else {
+            self.low_food_warned = false;
         }
     }
 
-    pub fn find_nearby_planet(&self, planets: &[Planet], star_position: Vec2) -> Option<usize> {
-        let proximity_threshold = 20.0;
+    // Whatever the ship can't cover is written off rather than blocking the landing
+    fn pay_docking_fee(&mut self, dock: DockId, system: &SolarSystem, ledger: &mut Ledger) {
+        let fee = system.docking_fee(dock).min(self.inventory.money.max(0));
+        self.inventory.money -= fee;
+        let location = system.dockable(dock).map(|b| b.name()).unwrap_or_default();
+        ledger.record(Transaction::new(TransactionKind::DockingFee, &self.name, location, -fee));
+    }
+
+    // Sell the hold at the local faction's terms. Contraband that got past
+    // customs goes to the black market instead.
+    fn sell_cargo(&mut self, dock: DockId, system: &mut SolarSystem, ledger: &mut Ledger, log: &mut MessageLog, rules: &GameRules) {
+        let terms: Vec<(ProductType, TradeTerms)> = ProductType::all()
+            .into_iter()
+            .map(|p| (p, system.trade_terms(dock, p, TradeSide::Sell)))
+            .collect();
+        let terms_for = |product: ProductType| {
+            terms.iter().find(|(p, _)| *p == product).map(|(_, t)| *t).unwrap_or(TradeTerms::standard())
+        };
+        let Some(body) = system.dockable_mut(dock) else { return };
+
+        let mut units_sold = 0;
+        let mut sold = 0;
+        let mut takings = 0;
+        let location = body.name().to_string();
+        for quote in TradeService::new(body.market_mut(), &mut self.inventory, rules).sell_all(terms_for) {
+            ledger.record_trade(&self.name, &location, &quote);
+            sold += quote.quantity;
+            takings += quote.total();
+            // Black market deals don't win any friends
+            if !terms_for(quote.product).black_market {
+                units_sold += quote.quantity;
+            }
+        }
+        if sold > 0 {
+            log.success(format!("{} sold {} units of cargo at {} for ${}", self.name, sold, location, takings));
+        }
+        system.adjust_reputation(dock, units_sold as f32 * REPUTATION_PER_TRADE_UNIT);
+    }
 
-        for (i, planet) in planets.iter().enumerate() {
-            let planet_pos = planet.position(star_position);
-            let distance = (self.position - planet_pos).length();
+    // Contraband the smuggler's compartment can't hide is seized and fined,
+    // and the faction remembers it
+    fn customs_scan(&mut self, dock: DockId, system: &mut SolarSystem, ledger: &mut Ledger, log: &mut MessageLog) {
+        let mut contraband: Vec<(ProductType, i32)> = self.inventory.cargo
+            .iter()
+            .filter(|&(&product, _)| system.is_contraband(dock, product))
+            .map(|(&product, &amount)| (product, amount))
+            .collect();
+        if contraband.is_empty() || rand::gen_range(0.0, 1.0) >= CUSTOMS_SCAN_CHANCE {
+            return;
+        }
+        // Most valuable goods go in the compartment first
+        let prices = system.dockable(dock).map(|body| body.market().prices);
+        contraband.sort_by_key(|&(product, _)| std::cmp::Reverse(prices.map_or(0, |prices| prices.sell_price(product))));
 
-            if distance < proximity_threshold + planet.radius {
-                return Some(i);
+        let compartment_found = rand::gen_range(0.0, 1.0) < COMPARTMENT_DETECTION_CHANCE;
+        let mut hiding_space = match self.smuggler_compartment {
+            Some(compartment) if !compartment_found => compartment.capacity,
+            _ => 0,
+        };
+        let mut seized = 0;
+        for (product, amount) in contraband {
+            let hidden = amount.min(hiding_space);
+            hiding_space -= hidden;
+            if amount > hidden && self.inventory.remove_cargo(product, amount - hidden).is_ok() {
+                seized += amount - hidden;
             }
         }
 
-        None
+        let location = system.dockable(dock).map(|b| b.name().to_string()).unwrap_or_default();
+        if seized == 0 {
+            log.info(format!("{} passed a customs scan at {} - nothing found", self.name, location));
+            return;
+        }
+        let fine = (seized * CONTRABAND_FINE_PER_UNIT).min(self.inventory.money.max(0));
+        self.inventory.money -= fine;
+        ledger.record(Transaction::new(TransactionKind::Fine, &self.name, &location, -fine));
+        system.adjust_reputation(dock, -REPUTATION_PER_SMUGGLING_BUST);
+        log.error(format!(
+            "Customs at {} seized {} units of contraband from {} and fined ${}",
+            location, seized, self.name, fine,
+        ));
     }
 
-    pub fn is_near_planet(&self, planets: &[Planet], star_position: Vec2) -> bool {
-        self.state == SpaceshipState::Flying && self.find_nearby_planet(planets, star_position).is_some()
+    pub fn hull_fraction(&self) -> f32 {
+        self.hull / MAX_HULL
     }
 
-    pub fn draw(&self, camera_offset: Vec2) {
-        // Draw a triangle pointing in the direction of rotation
-        let angle = self.rotation;
-        let screen_pos = self.position - camera_offset;
+    pub fn effective_speed(&self) -> f32 {
+        if DAMAGE_DEGRADES_SHIP {
+            self.speed * (0.5 + 0.5 * self.hull_fraction())
+        } else {
+            self.speed
+        }
+    }
 
-        // Calculate triangle vertices relative to rotation
-        let tip = Vec2::new(
-            screen_pos.x + (self.size * angle.cos()),
-            screen_pos.y + (self.size * angle.sin()),
-        );
+    pub fn take_damage(&mut self, amount: f32) {
+        self.hull = (self.hull - amount).max(0.0);
+        // Small steady damage, like the star's heat, only throws the odd spark
+        let sparks = (amount * SPARKS_PER_DAMAGE + rand::gen_range(0.0, 1.0)) as usize;
+        self.particles.emit_sparks(self.position, sparks);
+    }
 
-        let left = Vec2::new(
-            screen_pos.x + (self.size * 0.6 * (angle + 2.5).cos()),
-            screen_pos.y + (self.size * 0.6 * (angle + 2.5).sin()),
-        );
+    pub fn repair(&mut self, amount: f32) {
+        self.hull = (self.hull + amount).min(MAX_HULL);
+    }
 
-        let right = Vec2::new(
-            screen_pos.x + (self.size * 0.6 * (angle - 2.5).cos()),
-            screen_pos.y + (self.size * 0.6 * (angle - 2.5).sin()),
-        );
+    // Damage from hitting something solid, with a short grace period so
+    // scraping along a surface isn't fatal in a fraction of a second
+    fn impact(&mut self, damage: f32) {
+        if self.collision_cooldown <= 0.0 {
+            self.take_damage(damage);
+            self.collision_cooldown = COLLISION_COOLDOWN;
+        }
+    }
+
+    // The star burns anything that gets close and is solid like planets and moons
+    fn collide_with_bodies(&mut self, dt: f32, system: &SolarSystem) {
+        let ship_radius = self.size * 0.6;
+
+        let star = &system.star;
+        let from_star = self.position - star.position;
+        let star_distance = from_star.length();
+        let heat_radius = star.radius * STAR_HEAT_RADIUS_FACTOR;
+        if star_distance < heat_radius {
+            let intensity = 1.0 - ((star_distance - star.radius) / (heat_radius - star.radius)).clamp(0.0, 1.0);
+            self.take_damage(STAR_HEAT_DAMAGE_PER_SECOND * intensity * dt);
+        }
+        if star_distance < star.radius + ship_radius {
+            self.position = star.position + from_star.normalize_or(Vec2::X) * (star.radius + ship_radius);
+            self.velocity = Vec2::ZERO;
+            self.impact(COLLISION_DAMAGE);
+        }
+
+        // Stations are small and flimsy enough to fly around, only planets and moons are solid
+        for dock in system.dock_ids().into_iter().filter(|d| !d.is_station()) {
+            let (Some(pos), Some(body)) = (system.dock_position(dock), system.dockable(dock)) else {
+                continue;
+            };
+            let offset = self.position - pos;
+            let min_distance = body.radius() + ship_radius;
+            if offset.length() < min_distance {
+                self.position = pos + offset.normalize_or(Vec2::X) * min_distance;
+                self.velocity = Vec2::ZERO;
+                self.impact(COLLISION_DAMAGE);
+            }
+        }
+    }
+
+    // Puffs from every nozzle, more of them the harder the engines are working
+    fn emit_exhaust(&mut self, dt: f32, throttle: f32) {
+        let facing = Vec2::from_angle(self.rotation);
+        for &(x, y) in self.hull_style.engines() {
+            let nozzle = self.position + facing.rotate(vec2(x, y)) * self.size;
+            let puffs = (EXHAUST_RATE * throttle * dt + rand::gen_range(0.0, 1.0)) as usize;
+            for _ in 0..puffs {
+                self.particles.emit_exhaust(nozzle, -facing, self.size * 0.18);
+            }
+        }
+    }
+
+    // Either the insurer tows what's left to the nearest planet, or the run is over
+    fn on_hull_breached(&mut self, system: &SolarSystem, ledger: &mut Ledger) {
+        if !self.insured {
+            self.destroyed = true;
+            self.velocity = Vec2::ZERO;
+            self.particles.emit_sparks(self.position, 60);
+            return;
+        }
+
+        self.insured = false;
+        self.inventory.money += INSURANCE_PAYOUT;
+        self.inventory.cargo.clear();
+        self.hull = MAX_HULL;
+        self.mining_target = None;
+
+        let nearest_planet = (0..system.planets.len())
+            .filter_map(|p| system.dock_position(DockId::Planet(p)).map(|pos| (p, pos)))
+            .min_by(|(_, a), (_, b)| {
+                (*a - self.position).length().total_cmp(&(*b - self.position).length())
+            });
+        if let Some((p, pos)) = nearest_planet {
+            // Set down on the side it was towed in from
+            let radius = system.planets[p].radius;
+            self.surface_offset = (self.position - pos).normalize_or(Vec2::X) * radius;
+            self.docked_at = Some(DockId::Planet(p));
+            self.position = pos + self.surface_offset;
+            self.state = SpaceshipState::Landed;
+            self.size = self.base_size * LANDED_SCALE;
+            self.velocity = Vec2::ZERO;
+            self.docking_arc = None;
+        }
+
+        let location = self.docked_at.and_then(|dock| system.dockable(dock)).map(|b| b.name()).unwrap_or("Deep space");
+        ledger.record(Transaction::new(TransactionKind::Insurance, &self.name, location, INSURANCE_PAYOUT));
+    }
+
+    // Asteroids are solid: push the ship back out and kill its momentum
+    fn collide_with_asteroids(&mut self, system: &SolarSystem) {
+        let ship_radius = self.size * 0.6;
+        if let Some((b, a, distance)) = system.nearest_asteroid(self.position, ship_radius) {
+            let asteroid_pos = system.belts[b].asteroids[a].position(system.star.position);
+            let push = (self.position - asteroid_pos).normalize_or(Vec2::X);
+            self.position += push * (ship_radius - distance);
+            self.velocity = Vec2::ZERO;
+            self.impact(ASTEROID_COLLISION_DAMAGE);
+        }
+    }
+
+    fn update_mining(&mut self, dt: f32, system: &mut SolarSystem) {
+        let laser_ready = self.mining_laser.is_some_and(|laser| !laser.is_broken());
+        let target = system.nearest_asteroid(self.position, MINING_RANGE).map(|(b, a, _)| (b, a));
+
+        if !self.mining || !laser_ready || target.is_none() || self.inventory.available_space() < 1 {
+            self.mining_progress = 0.0;
+            self.mining_target = None;
+            return;
+        }
+
+        // Switching asteroids restarts the cut
+        if target != self.mining_target {
+            self.mining_progress = 0.0;
+            self.mining_target = target;
+        }
+
+        self.mining_progress += dt;
+        if self.mining_progress < MINING_TIME {
+            return;
+        }
+        self.mining_progress = 0.0;
+
+        let Some((b, a)) = target else { return };
+        let asteroid = &mut system.belts[b].asteroids[a];
+        let product = if asteroid.rare || rand::gen_range(0.0, 1.0) < RARE_ORE_CHANCE {
+            ProductType::RareOre
+        } else {
+            ProductType::Minerals
+        };
+        // Ore that doesn't fit stays in the rock
+        if self.inventory.add_cargo(product, 1).is_err() {
+            return;
+        }
+        asteroid.ore -= 1;
+
+        if let Some(laser) = self.mining_laser.as_mut() {
+            laser.wear();
+        }
+
+        // The asteroid is removed on the next belt update once it runs dry
+        if asteroid.is_depleted() {
+            self.mining_target = None;
+        }
+    }
+
+    pub fn can_mine(&self, system: &SolarSystem) -> bool {
+        self.state == SpaceshipState::Flying
+            && self.mining_laser.is_some_and(|laser| !laser.is_broken())
+            && system.nearest_asteroid(self.position, MINING_RANGE).is_some()
+    }
 
-        // Draw the triangle
-        draw_triangle(tip, left, right, WHITE);
+    fn follow_dock(&mut self, system: &SolarSystem) {
+        if let Some(pos) = self.docked_at.and_then(|dock| system.dock_position(dock)) {
+            self.position = pos + self.surface_offset;
+        }
+    }
+
+    // Along the landing or takeoff curve, nose pointing the way it's going
+    fn follow_docking_arc(&mut self, system: &SolarSystem) {
+        let Some(arc) = self.docking_arc else { return };
+        let Some(pos) = self.docked_at.and_then(|dock| system.dock_position(dock)) else {
+            return;
+        };
+        self.position = pos + arc.point(self.animation_progress);
+        let direction = arc.direction(self.animation_progress);
+        self.rotation = direction.y.atan2(direction.x);
+    }
+
+    // Closest landable body within range. Moons and stations sit close to
+    // their planet, so the nearest one wins rather than the first found.
+    pub fn find_nearby_dock(&self, system: &SolarSystem) -> Option<DockId> {
+        let mut nearest: Option<(DockId, f32)> = None;
+        for dock in system.dock_ids() {
+            let Some(distance) = self.distance_to_dock(dock, system) else {
+                continue;
+            };
+
+            if distance < DOCKING_RANGE && nearest.is_none_or(|(_, d)| distance < d) {
+                nearest = Some((dock, distance));
+            }
+        }
+
+        nearest.map(|(dock, _)| dock)
+    }
+
+    // Distance from the ship to a body's surface
+    fn distance_to_dock(&self, dock: DockId, system: &SolarSystem) -> Option<f32> {
+        let pos = system.dock_position(dock)?;
+        let body = system.dockable(dock)?;
+        Some((self.position - pos).length() - body.radius())
+    }
+
+    pub fn can_dock_at(&self, dock: DockId, system: &SolarSystem) -> bool {
+        self.distance_to_dock(dock, system).is_some_and(|distance| distance < DOCKING_RANGE)
+    }
+
+    pub fn is_near_dock(&self, system: &SolarSystem) -> bool {
+        self.state == SpaceshipState::Flying && self.find_nearby_dock(system).is_some()
+    }
+
+    // The way the ship is heading: its current velocity, or straight off the
+    // nose at full speed while it's holding still
+    fn heading_velocity(&self) -> Vec2 {
+        if self.velocity != Vec2::ZERO {
+            self.velocity
+        } else {
+            Vec2::from_angle(self.rotation) * self.effective_speed()
+        }
+    }
+
+    // Where the ship will be over the next few seconds if it keeps on as it is,
+    // stepped against the bodies' orbits so it ends where it would hit one
+    pub fn predicted_path(&self, system: &SolarSystem) -> Vec<Vec2> {
+        if self.state != SpaceshipState::Flying || self.inventory.fuel <= 0.0 {
+            return Vec::new();
+        }
+        let velocity = self.heading_velocity();
+        let step = PREDICTION_TIME / PREDICTION_STEPS as f32;
+        let solid: Vec<DockId> = system.dock_ids().into_iter().filter(|d| !d.is_station()).collect();
+        let mut position = self.position;
+        let mut path = vec![position];
+        for i in 1..=PREDICTION_STEPS {
+            position += velocity * step;
+            path.push(position);
+            let seconds = i as f32 * step;
+            let hits_star = position.distance(system.star.position) < system.star.radius;
+            let hits_body = solid.iter().any(|&dock| {
+                let (Some(pos), Some(body)) = (system.predict_dock_position(dock, seconds), system.dockable(dock)) else {
+                    return false;
+                };
+                position.distance(pos) < body.radius()
+            });
+            if hits_star || hits_body {
+                break;
+            }
+        }
+        path
+    }
+
+    // What the ship is flying to: its autopilot's next stop, or otherwise
+    // whichever body it will meet closest to dead ahead
+    pub fn heading_target(&self, system: &SolarSystem) -> Option<DockId> {
+        if self.state != SpaceshipState::Flying {
+            return None;
+        }
+        if let Some(target) = self.autopilot.as_ref().and_then(|autopilot| autopilot.target()) {
+            return Some(target);
+        }
+        let heading = self.heading_velocity();
+        let mut best: Option<(DockId, f32)> = None;
+        for dock in system.dock_ids() {
+            let Some((aim, _)) = system.intercept(self.position, self.effective_speed(), dock) else {
+                continue;
+            };
+            let angle = heading.angle_between(aim - self.position).abs();
+            if angle < HEADING_CONE && best.is_none_or(|(_, a)| angle < a) {
+                best = Some((dock, angle));
+            }
+        }
+        best.map(|(dock, _)| dock)
+    }
+
+    // The predicted path as a fading dashed line, and a ghost of the target
+    // body where it will be when the ship gets there
+    pub fn draw_flight_path(&self, camera_offset: Vec2, system: &SolarSystem) {
+        let path = self.predicted_path(system);
+        for (i, pair) in path.windows(2).enumerate().step_by(2) {
+            let alpha = 0.6 * (1.0 - i as f32 / path.len() as f32);
+            let (from, to) = (pair[0] - camera_offset, pair[1] - camera_offset);
+            draw_line(from.x, from.y, to.x, to.y, 1.5, Color::new(0.4, 0.8, 1.0, alpha));
+        }
+
+        let Some(target) = self.heading_target(system) else { return };
+        let (Some((aim, eta)), Some(body)) = (system.intercept(self.position, self.effective_speed(), target), system.dockable(target)) else {
+            return;
+        };
+        let pos = aim - camera_offset;
+        let radius = body.radius() + 4.0;
+        let color = Color::new(1.0, 0.85, 0.3, 0.8);
+        draw_circle_lines(pos.x, pos.y, radius, 1.5, color);
+        for corner in 0..4 {
+            let direction = Vec2::from_angle(corner as f32 * std::f32::consts::FRAC_PI_2);
+            let (inner, outer) = (pos + direction * (radius + 3.0), pos + direction * (radius + 9.0));
+            draw_line(inner.x, inner.y, outer.x, outer.y, 1.5, color);
+        }
+        let label = format!("{} - {:.0}s", body.name(), eta);
+        draw_text(&label, pos.x + radius + 10.0, pos.y - radius, 16.0, color);
+    }
+
+    pub fn draw(&self, camera_offset: Vec2, system: &SolarSystem) {
+        // Exhaust and dust go behind the ship, and outlast a destroyed one
+        self.particles.draw(camera_offset);
+        if self.destroyed {
+            return;
+        }
+
+        // Mining laser beam
+        if let Some((b, a)) = self.mining_target
+            && let Some(asteroid) = system.belts.get(b).and_then(|belt| belt.asteroids.get(a))
+        {
+            let from = self.position - camera_offset;
+            let to = asteroid.position(system.star.position) - camera_offset;
+            let flicker = 0.6 + 0.4 * (get_time() as f32 * 30.0).sin().abs();
+            draw_line(from.x, from.y, to.x, to.y, 2.0, Color::new(1.0, 0.3, 0.2, flicker));
+            draw_circle(to.x, to.y, 3.0 + self.mining_progress / MINING_TIME * 3.0, Color::new(1.0, 0.8, 0.3, flicker));
+        }
 
-        // Draw outline for better visibility
-        draw_line(tip.x, tip.y, left.x, left.y, 2.0, SKYBLUE);
-        draw_line(left.x, left.y, right.x, right.y, 2.0, SKYBLUE);
-        draw_line(right.x, right.y, tip.x, tip.y, 2.0, SKYBLUE);
+        let thrusting = match self.state {
+            SpaceshipState::Flying => self.velocity.length() > 0.0 && self.inventory.fuel > 0.0,
+            SpaceshipState::Landing | SpaceshipState::TakingOff => true,
+            SpaceshipState::Landed => false,
+        };
+        self.hull_style.draw(self.position - camera_offset, self.rotation, self.size, thrusting);
     }
 }
@@ -1,5 +1,16 @@
 use macroquad::prelude::*;
 
+const GLOW_LAYERS: usize = 6;
+const CORONA_PULSE_SPEED: f32 = 1.4;  // Radians per second
+const SURFACE_SPOTS: usize = 14;
+const SURFACE_SPIN_SPEED: f32 = 0.15; // Radians per second
+const FLARE_COUNT: usize = 4;
+const FLARE_PERIOD: f32 = 3.5;        // Seconds each flare takes to rise and fall
+const DISK_RINGS: usize = 4;
+const DISK_SEGMENTS: usize = 48;
+const DISK_FLATTENING: f32 = 0.28;    // How edge-on the accretion disk is seen
+const DISK_TILT: f32 = -0.2;
+
 #[derive(Clone, Copy, Debug)]
 pub enum StarType {
     YellowDwarf,
@@ -18,6 +29,16 @@ impl StarType {
         }
     }
 
+    // Colour of the nebulae in the background
+    pub fn nebula_tint(&self) -> Color {
+        match self {
+            StarType::YellowDwarf => Color::new(0.85, 0.55, 0.3, 1.0),
+            StarType::RedDwarf => Color::new(0.75, 0.15, 0.25, 1.0),
+            StarType::BlueGiant => Color::new(0.3, 0.55, 0.95, 1.0),
+            StarType::BlackHole => Color::new(0.45, 0.1, 0.65, 1.0),
+        }
+    }
+
     pub fn radius(&self) -> f32 {
         match self {
             StarType::YellowDwarf => 30.0,
@@ -35,6 +56,12 @@ impl StarType {
             StarType::BlackHole => "Black Hole",
         }
     }
+
+    pub fn parse(name: &str) -> Option<StarType> {
+        [StarType::YellowDwarf, StarType::RedDwarf, StarType::BlueGiant, StarType::BlackHole]
+            .into_iter()
+            .find(|star_type| star_type.name() == name)
+    }
 }
 
 pub struct Star {
@@ -56,11 +83,124 @@ impl Star {
 
     pub fn draw(&self, camera_offset: Vec2) {
         let screen_pos = self.position - camera_offset;
-        draw_circle(screen_pos.x, screen_pos.y, self.radius, self.color);
+        let time = get_time() as f32;
+
+        match self.star_type {
+            StarType::YellowDwarf => {
+                // Corona breathing in and out around a churning surface
+                let pulse = (time * CORONA_PULSE_SPEED).sin() * 0.5 + 0.5;
+                draw_glow(screen_pos, self.radius, self.color, 1.6 + pulse * 0.3, 0.10 + pulse * 0.04);
+                draw_circle(screen_pos.x, screen_pos.y, self.radius * (1.08 + pulse * 0.05), Color::new(1.0, 0.85, 0.4, 0.35));
+                draw_circle(screen_pos.x, screen_pos.y, self.radius, self.color);
+                self.draw_surface(screen_pos, time, Color::new(1.0, 1.0, 0.75, 0.35));
+            }
+            StarType::RedDwarf => {
+                // A dim, slowly flickering glow with dark spots drifting across
+                let flicker = (time * 0.7).sin() * 0.5 + 0.5;
+                draw_glow(screen_pos, self.radius, self.color, 1.2, 0.08 + flicker * 0.04);
+                draw_circle(screen_pos.x, screen_pos.y, self.radius, self.color);
+                self.draw_surface(screen_pos, time * 0.5, Color::new(0.35, 0.05, 0.02, 0.45));
+            }
+            StarType::BlueGiant => {
+                draw_glow(screen_pos, self.radius, self.color, 2.2, 0.12);
+                self.draw_flares(screen_pos, time);
+                draw_circle(screen_pos.x, screen_pos.y, self.radius, self.color);
+                self.draw_surface(screen_pos, time * 1.5, Color::new(0.9, 0.95, 1.0, 0.3));
+            }
+            StarType::BlackHole => self.draw_black_hole(screen_pos, time),
+        }
+    }
+
+    // Bright granules rotating with the star
+    fn draw_surface(&self, pos: Vec2, time: f32, color: Color) {
+        for i in 0..SURFACE_SPOTS {
+            let t = i as f32 / SURFACE_SPOTS as f32;
+            // Spread over the disc, nearer the rim the more of them there are
+            let angle = t * std::f32::consts::TAU * 2.618 + time * SURFACE_SPIN_SPEED;
+            let distance = self.radius * (0.25 + 0.6 * ((i * 7 % SURFACE_SPOTS) as f32 / SURFACE_SPOTS as f32));
+            let wobble = (time * 1.3 + i as f32).sin() * 0.5 + 0.5;
+            let spot = pos + Vec2::from_angle(angle) * distance;
+            draw_circle(spot.x, spot.y, self.radius * (0.08 + wobble * 0.06), color);
+        }
+    }
 
-        // Black hole special effect - draw event horizon
-        if matches!(self.star_type, StarType::BlackHole) {
-            draw_circle_lines(screen_pos.x, screen_pos.y, self.radius + 5.0, 2.0, Color::new(0.5, 0.0, 0.8, 0.8));
+    // Loops of plasma that rise off the surface and fall back, each erupting in turn
+    fn draw_flares(&self, pos: Vec2, time: f32) {
+        for i in 0..FLARE_COUNT {
+            let cycle = time / FLARE_PERIOD + i as f32 / FLARE_COUNT as f32;
+            let phase = cycle.fract();
+            // Each eruption comes out somewhere new
+            let angle = (cycle.floor() * 2.39996 + i as f32 * 1.7) % std::f32::consts::TAU;
+            let height = (phase * std::f32::consts::PI).sin() * self.radius * 0.7;
+            let base = pos + Vec2::from_angle(angle) * self.radius * 0.9;
+            let tip = pos + Vec2::from_angle(angle) * (self.radius + height);
+            let fade = 1.0 - phase;
+            draw_line(base.x, base.y, tip.x, tip.y, self.radius * 0.12, Color::new(0.6, 0.8, 1.0, 0.25 * fade));
+            draw_line(base.x, base.y, tip.x, tip.y, self.radius * 0.05, Color::new(0.9, 0.95, 1.0, 0.6 * fade));
+            draw_circle(tip.x, tip.y, self.radius * 0.07, Color::new(0.8, 0.9, 1.0, 0.5 * fade));
         }
     }
+
+    // The disk's far half is drawn first and hidden by the hole; its light is
+    // bent over the top and under the bottom of the shadow as the lensed ring,
+    // then the near half passes in front of everything.
+    fn draw_black_hole(&self, pos: Vec2, time: f32) {
+        draw_glow(pos, self.radius, Color::new(0.45, 0.15, 0.7, 1.0), 2.0, 0.07);
+        self.draw_accretion_disk(pos, time, false);
+
+        // Lensed image of the far side of the disk, brightest above and below the shadow
+        let ring_radius = self.radius * 1.35;
+        for i in 0..DISK_SEGMENTS {
+            let a0 = i as f32 / DISK_SEGMENTS as f32 * std::f32::consts::TAU;
+            let a1 = (i + 1) as f32 / DISK_SEGMENTS as f32 * std::f32::consts::TAU;
+            let vertical = a0.sin().abs();
+            let p0 = pos + Vec2::from_angle(a0) * ring_radius;
+            let p1 = pos + Vec2::from_angle(a1) * ring_radius;
+            let color = Color::new(1.0, 0.65, 0.3, 0.25 + vertical * 0.45);
+            draw_line(p0.x, p0.y, p1.x, p1.y, self.radius * (0.06 + vertical * 0.14), color);
+        }
+
+        draw_circle(pos.x, pos.y, self.radius, BLACK);
+        // Photon ring right at the edge of the shadow
+        draw_circle_lines(pos.x, pos.y, self.radius * 1.04, 1.5, Color::new(1.0, 0.9, 0.7, 0.8));
+
+        self.draw_accretion_disk(pos, time, true);
+    }
+
+    // Rings of gas seen nearly edge-on, inner rings orbiting faster. The side
+    // turning towards the viewer is brighter.
+    fn draw_accretion_disk(&self, pos: Vec2, time: f32, near_half: bool) {
+        let tilt = Vec2::from_angle(DISK_TILT);
+        for ring in 0..DISK_RINGS {
+            let t = ring as f32 / (DISK_RINGS - 1) as f32;
+            let radius = self.radius * (1.5 + t * 1.0);
+            let spin = time * 1.2 / (1.0 + t * 2.0);
+            let heat = 1.0 - t;
+            for i in 0..DISK_SEGMENTS {
+                let a0 = i as f32 / DISK_SEGMENTS as f32 * std::f32::consts::TAU;
+                let a1 = (i + 1) as f32 / DISK_SEGMENTS as f32 * std::f32::consts::TAU;
+                // The near half is the lower one on screen
+                if (a0.sin() > 0.0) != near_half {
+                    continue;
+                }
+                let p0 = pos + tilt.rotate(vec2(a0.cos(), a0.sin() * DISK_FLATTENING) * radius);
+                let p1 = pos + tilt.rotate(vec2(a1.cos(), a1.sin() * DISK_FLATTENING) * radius);
+                // Clumps in the gas stream around with the ring
+                let clump = ((a0 - spin) * 3.0).sin() * 0.5 + 0.5;
+                let doppler = 0.6 + 0.4 * a0.cos();
+                let brightness = (0.5 + clump * 0.5) * doppler;
+                let color = Color::new(1.0 * brightness, (0.45 + heat * 0.45) * brightness, (0.15 + heat * 0.5) * brightness, 0.8);
+                draw_line(p0.x, p0.y, p1.x, p1.y, self.radius * 0.16, color);
+            }
+        }
+    }
+}
+
+// Soft light around a star, faded over a few layers out to `extent` radii past the surface
+fn draw_glow(pos: Vec2, radius: f32, color: Color, extent: f32, alpha: f32) {
+    for i in (0..GLOW_LAYERS).rev() {
+        let t = (i + 1) as f32 / GLOW_LAYERS as f32;
+        let layer_alpha = alpha * (1.0 - t * 0.8);
+        draw_circle(pos.x, pos.y, radius * (1.0 + extent * t), Color::new(color.r, color.g, color.b, layer_alpha));
+    }
 }
@@ -0,0 +1,136 @@
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+use super::StarType;
+
+const TILE_SIZE: f32 = 1024.0;        // Each star layer repeats every this many pixels
+const NEBULA_TILE_SIZE: f32 = 2600.0;
+const NEBULA_PARALLAX: f32 = 0.02;    // Nebulae are the furthest thing out, they barely move
+const NEBULA_CLOUDS: usize = 5;       // Per tile
+const NEBULA_PUFFS: usize = 7;        // Overlapping blobs that make up one cloud
+const NEBULA_ALPHA: f32 = 0.035;
+
+// How far each layer moves with the camera, how many stars it has per tile
+// and how big its largest stars are. Nearer layers move more and are sparser.
+const STAR_LAYERS: [(f32, usize, f32); 3] = [(0.05, 220, 1.0), (0.15, 110, 1.6), (0.35, 45, 2.4)];
+
+struct BackgroundStar {
+    position: Vec2, // Within the layer's tile
+    size: f32,
+    color: Color,
+}
+
+struct StarLayer {
+    parallax: f32,
+    stars: Vec<BackgroundStar>,
+}
+
+struct NebulaCloud {
+    position: Vec2,          // Within the nebula tile
+    puffs: Vec<(Vec2, f32)>, // Offset from the cloud's centre and radius of each blob
+    extent: f32,             // How far the blobs reach, so clouds near the edge aren't cut off
+}
+
+// The backdrop behind a system: layers of distant stars scrolling slower than
+// the camera, and faint nebulae tinted by the star. Generated once from the
+// system's seed, so a system always has the same sky.
+pub struct Starfield {
+    layers: Vec<StarLayer>,
+    clouds: Vec<NebulaCloud>,
+    tint: Color,
+}
+
+impl Starfield {
+    pub fn new(seed: u64, star_type: StarType) -> Self {
+        // A generator of its own, so the sky doesn't change what else gets generated
+        let rng = RandGenerator::new();
+        rng.srand(seed);
+        let tint = star_type.nebula_tint();
+
+        let layers = STAR_LAYERS
+            .iter()
+            .map(|&(parallax, count, max_size)| StarLayer {
+                parallax,
+                stars: (0..count)
+                    .map(|_| {
+                        // Mostly white, some picking up a little of the nebula's colour
+                        let brightness = rng.gen_range(0.35, 1.0);
+                        let warmth = rng.gen_range(0.0, 0.3);
+                        BackgroundStar {
+                            position: vec2(rng.gen_range(0.0, TILE_SIZE), rng.gen_range(0.0, TILE_SIZE)),
+                            size: rng.gen_range(max_size * 0.4, max_size),
+                            color: Color::new(
+                                brightness * (1.0 - warmth + warmth * tint.r),
+                                brightness * (1.0 - warmth + warmth * tint.g),
+                                brightness * (1.0 - warmth + warmth * tint.b),
+                                1.0,
+                            ),
+                        }
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        let clouds = (0..NEBULA_CLOUDS)
+            .map(|_| {
+                let size = rng.gen_range(120.0, 320.0);
+                let puffs: Vec<(Vec2, f32)> = (0..NEBULA_PUFFS)
+                    .map(|_| {
+                        let offset = vec2(rng.gen_range(-size, size), rng.gen_range(-size, size) * 0.6);
+                        (offset, rng.gen_range(size * 0.4, size))
+                    })
+                    .collect();
+                let extent = puffs.iter().map(|(offset, radius)| offset.length() + radius).fold(0.0, f32::max);
+                NebulaCloud {
+                    position: vec2(rng.gen_range(0.0, NEBULA_TILE_SIZE), rng.gen_range(0.0, NEBULA_TILE_SIZE)),
+                    puffs,
+                    extent,
+                }
+            })
+            .collect();
+
+        Self { layers, clouds, tint }
+    }
+
+    pub fn draw(&self, camera_offset: Vec2, show_nebula: bool) {
+        if show_nebula {
+            let color = Color::new(self.tint.r, self.tint.g, self.tint.b, NEBULA_ALPHA);
+            for cloud in &self.clouds {
+                for_each_copy(cloud.position, camera_offset * NEBULA_PARALLAX, NEBULA_TILE_SIZE, cloud.extent, |centre| {
+                    for (offset, radius) in &cloud.puffs {
+                        // A smaller, denser core on each blob softens the edge
+                        draw_circle(centre.x + offset.x, centre.y + offset.y, *radius, color);
+                        draw_circle(centre.x + offset.x, centre.y + offset.y, radius * 0.6, color);
+                    }
+                });
+            }
+        }
+
+        for layer in &self.layers {
+            for star in &layer.stars {
+                for_each_copy(star.position, camera_offset * layer.parallax, TILE_SIZE, star.size, |pos| {
+                    draw_rectangle(pos.x, pos.y, star.size, star.size, star.color);
+                });
+            }
+        }
+    }
+}
+
+// Calls `draw` at every on-screen copy of a point on a layer that repeats every
+// `tile` pixels and has scrolled by `scroll`. `margin` keeps things that reach
+// past the point from popping in at the screen edge.
+fn for_each_copy(point: Vec2, scroll: Vec2, tile: f32, margin: f32, mut draw: impl FnMut(Vec2)) {
+    let base = (point - scroll).rem_euclid(Vec2::splat(tile));
+    let mut x = base.x - tile * ((margin / tile).ceil() + 1.0);
+    while x < screen_width() + margin {
+        if x > -margin {
+            let mut y = base.y - tile * ((margin / tile).ceil() + 1.0);
+            while y < screen_height() + margin {
+                if y > -margin {
+                    draw(vec2(x, y));
+                }
+                y += tile;
+            }
+        }
+        x += tile;
+    }
+}
@@ -0,0 +1,42 @@
+use macroquad::prelude::*;
+use super::orbit::Orbit;
+use crate::economy::Market;
+use crate::rules::ProductPrices;
+
+pub struct Station {
+    pub name: String,
+    pub orbit: Orbit,
+    pub size: f32,
+    pub market: Market,
+}
+
+impl Station {
+    pub fn new(name: String, orbit: Orbit, size: f32, prices: ProductPrices) -> Self {
+        Self {
+            name,
+            orbit,
+            size,
+            // Stations don't produce anything, they only trade
+            market: Market::new(None, prices),
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.orbit.update(dt);
+        self.market.update(dt);
+    }
+
+    pub fn position(&self, parent: Vec2) -> Vec2 {
+        self.orbit.position(parent)
+    }
+
+    pub fn draw(&self, parent: Vec2, camera_offset: Vec2) {
+        let screen_pos = self.position(parent) - camera_offset;
+
+        // Draw as a rotating square so stations stand out from round bodies
+        draw_poly(screen_pos.x, screen_pos.y, 4, self.size, self.orbit.angle().to_degrees(), LIGHTGRAY);
+        draw_poly_lines(screen_pos.x, screen_pos.y, 4, self.size, self.orbit.angle().to_degrees(), 1.5, SKYBLUE);
+
+        self.orbit.draw(parent, camera_offset, Color::new(0.3, 0.3, 0.4, 0.3));
+    }
+}
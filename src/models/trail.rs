@@ -0,0 +1,46 @@
+use std::collections::VecDeque;
+use macroquad::prelude::*;
+
+const TRAIL_POINTS: usize = 60; // The oldest point drops off past this
+
+// Where something has been, sampled every `interval` seconds and drawn as a
+// line fading out towards its oldest end
+pub struct Trail {
+    points: VecDeque<Vec2>,
+    interval: f32,
+    timer: f32,
+}
+
+impl Trail {
+    pub fn new(interval: f32) -> Self {
+        Self {
+            points: VecDeque::with_capacity(TRAIL_POINTS),
+            interval,
+            timer: 0.0,
+        }
+    }
+
+    pub fn record(&mut self, dt: f32, position: Vec2) {
+        self.timer += dt;
+        if self.timer < self.interval && !self.points.is_empty() {
+            return;
+        }
+        self.timer = 0.0;
+        if self.points.len() >= TRAIL_POINTS {
+            self.points.pop_front();
+        }
+        self.points.push_back(position);
+    }
+
+    // `current` joins the newest sample to where the thing is right now
+    pub fn draw(&self, current: Vec2, camera_offset: Vec2, color: Color) {
+        let count = self.points.len();
+        let mut next = current - camera_offset;
+        for (i, &point) in self.points.iter().enumerate().rev() {
+            let point = point - camera_offset;
+            let alpha = color.a * (i + 1) as f32 / count as f32;
+            draw_line(point.x, point.y, next.x, next.y, 1.5, Color::new(color.r, color.g, color.b, alpha));
+            next = point;
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use macroquad::prelude::*;
+use crate::input::Action;
+use crate::menu::Menu;
+use crate::save::{self, SAVE_PATH};
+use crate::scene::{Context, Scene, Transition};
+use crate::settings_screen::SettingsScreen;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Item {
+    Resume,
+    Save,
+    Settings,
+    Quit,
+}
+
+const ITEMS: [Item; 4] = [Item::Resume, Item::Save, Item::Settings, Item::Quit];
+
+impl Item {
+    fn label(&self) -> &'static str {
+        match self {
+            Item::Resume => "Resume",
+            Item::Save => "Save game",
+            Item::Settings => "Settings",
+            Item::Quit => "Quit to menu",
+        }
+    }
+}
+
+// Overlay shown while the game is paused; nothing below it updates. Quitting
+// asks first, since anything unsaved is thrown away.
+pub struct PauseMenu {
+    selected: usize,
+    confirming_quit: bool,
+    clicked: Option<usize>, // A button clicked while drawing, handled with the keyboard input
+    status: Option<String>, // How the last save went
+}
+
+impl PauseMenu {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            confirming_quit: false,
+            clicked: None,
+            status: None,
+        }
+    }
+}
+
+impl Scene for PauseMenu {
+    fn handle_input(&mut self, ctx: &mut Context) -> Transition {
+        let bindings = &ctx.settings.bindings;
+        let clicked = self.clicked.take();
+        if self.confirming_quit {
+            // Clicks on the confirmation are 0 for yes, 1 for no
+            if bindings.is_pressed(Action::Confirm) || clicked == Some(0) {
+                ctx.game = None;
+                return Transition::Reset(Box::new(Menu::new()));
+            }
+            if bindings.is_pressed(Action::Back) || clicked == Some(1) {
+                self.confirming_quit = false;
+            }
+            return Transition::None;
+        }
+
+        if bindings.is_pressed(Action::Back) {
+            return Transition::Pop;
+        }
+        if bindings.is_pressed(Action::MenuUp) && self.selected > 0 {
+            self.selected -= 1;
+        }
+        if bindings.is_pressed(Action::MenuDown) && self.selected + 1 < ITEMS.len() {
+            self.selected += 1;
+        }
+        if let Some(i) = clicked {
+            self.selected = i;
+        }
+        if !bindings.is_pressed(Action::Confirm) && clicked.is_none() {
+            return Transition::None;
+        }
+        match ITEMS[self.selected] {
+            Item::Resume => Transition::Pop,
+            // Saving keeps the game paused, so the player can carry on or quit
+            Item::Save => {
+                let Some(game) = &ctx.game else { return Transition::None };
+                self.status = Some(match save::save(game, SAVE_PATH) {
+                    Ok(()) => "Game saved".to_string(),
+                    Err(err) => format!("Couldn't save: {}", err),
+                });
+                Transition::None
+            }
+            // Settings opens over the pause menu and comes back to it
+            Item::Settings => Transition::Push(Box::new(SettingsScreen::new())),
+            Item::Quit => {
+                self.confirming_quit = true;
+                Transition::None
+            }
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context) {
+        let ui = &mut ctx.ui;
+        let bindings = &ctx.settings.bindings;
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.5));
+        // Next to the time controls, which stay visible underneath
+        draw_text("PAUSED", screen_width() / 2.0 - 160.0, 24.0, 20.0, YELLOW);
+        let width = 320.0;
+        let height = 300.0;
+        let ui_x = screen_width() / 2.0 - width / 2.0;
+        let ui_y = screen_height() / 2.0 - height / 2.0;
+        draw_rectangle(ui_x, ui_y, width, height, Color::new(0.0, 0.0, 0.05, 0.95));
+        draw_rectangle_lines(ui_x, ui_y, width, height, 2.0, SKYBLUE);
+
+        if self.confirming_quit {
+            draw_text("Quit to the menu?", ui_x + 20.0, ui_y + 80.0, 20.0, WHITE);
+            draw_text("Anything unsaved will be lost.", ui_x + 20.0, ui_y + 105.0, 18.0, ORANGE);
+            if ui.button(Rect::new(ui_x + 20.0, ui_y + 140.0, 130.0, 30.0), "Quit", true) {
+                self.clicked = Some(0);
+            }
+            if ui.button(Rect::new(ui_x + 170.0, ui_y + 140.0, 130.0, 30.0), "Keep playing", true) {
+                self.clicked = Some(1);
+            }
+            let hint = format!("{} quit  {} cancel", bindings.label(Action::Confirm), bindings.label(Action::Back));
+            draw_text(&hint, ui_x + 20.0, ui_y + height - 20.0, 16.0, DARKGRAY);
+            return;
+        }
+
+        for (i, item) in ITEMS.iter().enumerate() {
+            let rect = Rect::new(ui_x + 20.0, ui_y + 60.0 + i as f32 * 40.0, width - 40.0, 32.0);
+            if i == self.selected {
+                draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::new(0.2, 0.3, 0.5, 0.5));
+            }
+            if ui.button(rect, item.label(), true) {
+                self.clicked = Some(i);
+            }
+        }
+        let hint = format!(
+            "{}/{} select  {} choose  {} resume",
+            bindings.label(Action::MenuUp),
+            bindings.label(Action::MenuDown),
+            bindings.label(Action::Confirm),
+            bindings.label(Action::Back),
+        );
+        draw_text(&hint, ui_x + 20.0, ui_y + height - 20.0, 16.0, DARKGRAY);
+        if let Some(status) = &self.status {
+            draw_text(status, ui_x + 20.0, ui_y + height - 68.0, 16.0, SKYBLUE);
+        }
+        if let Some(game) = &ctx.game {
+            draw_text(&format!("System seed {}", game.system.seed), ui_x + 20.0, ui_y + height - 44.0, 16.0, DARKGRAY);
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+
+    fn freezes_below(&self) -> bool {
+        true
+    }
+}
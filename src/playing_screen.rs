@@ -0,0 +1,304 @@
+use macroquad::prelude::*;
+use crate::economy::{LOW_FOOD_WARNING, LOW_FUEL_WARNING, TIME_SCALES};
+use crate::fleet_screen::FleetScreen;
+use crate::game_over_screen::GameOverScreen;
+use crate::input::Action;
+use crate::ledger_screen::LedgerScreen;
+use crate::menu::Menu;
+use crate::message_log_screen::MessageLogScreen;
+use crate::pause_menu::PauseMenu;
+use crate::scene::{Context, Scene, Transition};
+use crate::settings_screen::SettingsScreen;
+use crate::trade_screen::TradeScreen;
+
+// Longest slice of game time simulated in one step. Faster speeds take more
+// steps a frame, so ships don't skip through planets and asteroids.
+const MAX_STEP: f32 = 1.0 / 60.0;
+const MAX_STEPS_PER_FRAME: f32 = 20.0; // After a long stall the game slows rather than freezing
+
+// The flight view: the system, the fleet and the HUD. Docking, pausing and
+// the overlays are pushed on top of it.
+pub struct PlayingScreen {
+    focused: bool, // Nothing is covering it, so flight hints are shown
+}
+
+impl PlayingScreen {
+    pub fn new() -> Self {
+        Self { focused: true }
+    }
+}
+
+impl Scene for PlayingScreen {
+    fn handle_input(&mut self, ctx: &mut Context) -> Transition {
+        let Some(game) = ctx.game.as_ref() else { return Transition::Reset(Box::new(Menu::new())) };
+        if let Some(reason) = game.game_over_reason() {
+            return Transition::Push(Box::new(GameOverScreen::new(reason)));
+        }
+        if game.is_docked() {
+            return Transition::Push(Box::new(TradeScreen::new()));
+        }
+        hotkeys(ctx)
+    }
+
+    fn update(&mut self, ctx: &mut Context, focused: bool) {
+        self.focused = focused;
+        let Some(game) = ctx.game.as_mut() else { return };
+        let dt = get_frame_time() * ctx.settings.game_speed * game.time_scale;
+        let steps = (dt / MAX_STEP).ceil().clamp(1.0, MAX_STEPS_PER_FRAME);
+        for _ in 0..steps as usize {
+            game.update(dt / steps, focused.then_some(&ctx.settings.bindings));
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context) {
+        let Some(game) = ctx.game.as_ref() else { return };
+        let system = &game.system;
+        let fleet = &game.fleet;
+        let bindings = &ctx.settings.bindings;
+        let camera_offset = game.camera_offset();
+
+        // Draw everything with camera offset, the sky scrolling slower behind it
+        system.starfield.draw(camera_offset, ctx.settings.show_nebula);
+        system.draw(camera_offset);
+        if ctx.settings.show_trails {
+            system.draw_trails(camera_offset);
+            for (i, other) in fleet.ships.iter().enumerate().filter(|(_, ship)| !ship.destroyed) {
+                let color = if i == fleet.active { Color::new(0.4, 0.8, 1.0, 0.5) } else { Color::new(0.7, 0.7, 0.7, 0.35) };
+                other.trail.draw(other.position, camera_offset, color);
+            }
+        }
+        for hunter in &game.bounty_hunters {
+            hunter.draw(camera_offset);
+        }
+
+        // Draw the rest of the fleet with their names, then the ship under control on top
+        for (i, other) in fleet.ships.iter().enumerate() {
+            if i == fleet.active {
+                continue;
+            }
+            // A wreck only leaves its sparks behind
+            other.draw(camera_offset, system);
+            if !other.destroyed {
+                let label_pos = other.position - camera_offset;
+                draw_text(&other.name, label_pos.x + 12.0, label_pos.y - 12.0, 16.0, LIGHTGRAY);
+            }
+        }
+        let ship = fleet.active();
+        ship.draw_flight_path(camera_offset, system);
+        ship.draw(camera_offset, system);
+
+        // Everything from here on is HUD, sized by the HUD scale setting
+        ctx.ui.begin_hud(ctx.settings.hud_scale);
+        let (width, height) = (ctx.ui.width(), ctx.ui.height());
+
+        // Show blinking "Press SPACE to land" text when near a planet, moon or station
+        if self.focused {
+            let landing_denied_by = ship
+                .find_nearby_dock(system)
+                .filter(|&dock| !system.landing_allowed(dock))
+                .and_then(|dock| system.faction_of(dock))
+                .map(|faction| faction.name.clone());
+            if ship.is_near_dock(system) && let Some(ref faction) = landing_denied_by {
+                let text = format!("Landing denied - {} considers you hostile", faction);
+                let text_dims = measure_text(&text, None, 22, 1.0);
+                draw_text(&text, width / 2.0 - text_dims.width / 2.0, height - 100.0, 22.0, RED);
+            } else if ship.is_near_dock(system) && let Some(dock) = ship.find_nearby_dock(system) {
+                let alpha = ((get_time() * 3.0).sin() * 0.5 + 0.5) as f32;
+                let blink_color = Color::new(1.0, 1.0, 0.0, alpha);
+
+                // The fee is charged on landing, so say what it is before the player commits
+                let text = match system.docking_fee(dock) {
+                    0 => format!("Press {} to land - no docking fee", bindings.label(Action::Land)),
+                    fee => format!("Press {} to land - docking fee ${}", bindings.label(Action::Land), fee),
+                };
+                let text_size = 25.0;
+                let text_dims = measure_text(&text, None, text_size as u16, 1.0);
+                draw_text(&text, width / 2.0 - text_dims.width / 2.0, height - 100.0, text_size, blink_color);
+            } else if ship.can_mine(system) {
+                let text = format!("Hold {} to mine", bindings.label(Action::Mine));
+                let text_dims = measure_text(&text, None, 22, 1.0);
+                draw_text(&text, width / 2.0 - text_dims.width / 2.0, height - 100.0, 22.0, ORANGE);
+            }
+        }
+
+        // Display info
+        let star_name = system.star.star_type.name();
+        let planet_count = system.planets.len();
+        draw_text(&format!("Solar Economy - {} - {} Planets", star_name, planet_count), 10.0, 20.0, 20.0, WHITE);
+
+        // Display spaceship inventory and resources
+        {
+            let inv = &ship.inventory;
+            let y_start = 50.0;
+            let line_height = 20.0;
+
+            // Money
+            draw_text(&format!("{} - Money: ${}", ship.name, inv.money), 10.0, y_start, 18.0, GOLD);
+
+            // Fuel (red once the low fuel warning is due)
+            let fuel_color = if inv.fuel < LOW_FUEL_WARNING { RED } else { WHITE };
+            draw_text(&format!("Fuel: {:.1}", inv.fuel), 10.0, y_start + line_height, 18.0, fuel_color);
+
+            // Food (red once the low food warning is due)
+            let food_color = if inv.food < LOW_FOOD_WARNING { RED } else { WHITE };
+            draw_text(&format!("Food: {}", inv.food), 10.0, y_start + line_height * 2.0, 18.0, food_color);
+
+            // Cargo
+            draw_text(
+                &format!("Cargo: {}/{}", inv.total_cargo(), inv.capacity),
+                10.0,
+                y_start + line_height * 3.0,
+                18.0,
+                WHITE,
+            );
+
+            // List cargo items
+            for (i, (product, amount)) in inv.cargo.iter().enumerate() {
+                draw_text(
+                    &format!("  {}: {}", product.name(), amount),
+                    10.0,
+                    y_start + line_height * (4.0 + i as f32),
+                    16.0,
+                    GRAY,
+                );
+            }
+
+            // Hull (red if badly damaged)
+            let hull_y = y_start + line_height * (4.0 + inv.cargo.len() as f32);
+            let hull_color = if ship.hull_fraction() < 0.3 { RED } else { WHITE };
+            let insured = if ship.insured { " (insured)" } else { "" };
+            draw_text(&format!("Hull: {:.0}%{}", ship.hull_fraction() * 100.0, insured), 10.0, hull_y, 18.0, hull_color);
+
+            // Mining laser condition
+            if let Some(laser) = ship.mining_laser {
+                let laser_y = hull_y + line_height;
+                let (text, color) = if laser.is_broken() {
+                    ("Mining Laser: BROKEN".to_string(), RED)
+                } else {
+                    (format!("Mining Laser: {:.0}%", laser.condition * 100.0), if laser.condition < 0.25 { ORANGE } else { WHITE })
+                };
+                draw_text(&text, 10.0, laser_y, 18.0, color);
+            }
+        }
+
+        // Debt and savings in the top right
+        let bank = &game.bank;
+        let bank_x = width - 230.0;
+        if bank.total_debt() > 0 {
+            draw_text(&format!("Debt: ${}", bank.total_debt()), bank_x, 50.0, 18.0, ORANGE);
+            if let Some(due_at) = bank.next_due() {
+                let remaining = due_at - game.time;
+                let color = if remaining < 30.0 { RED } else { WHITE };
+                draw_text(
+                    &format!("Next payment due in {}", format_duration(remaining)),
+                    bank_x,
+                    70.0,
+                    16.0,
+                    color,
+                );
+            }
+        }
+        if bank.savings >= 1.0 {
+            draw_text(&format!("Savings: ${}", bank.savings.floor()), bank_x, 90.0, 16.0, GOLD);
+        }
+        if !game.bounty_hunters.is_empty() {
+            draw_text("BOUNTY HUNTERS IN PURSUIT", bank_x, 110.0, 16.0, RED);
+        }
+
+        game.messages.draw_toasts(width);
+
+        if fleet.ships.len() > 1 {
+            draw_text(
+                &format!("Fleet: {} ships - press {} to manage", fleet.ships.len(), bindings.label(Action::OpenFleet)),
+                bank_x,
+                130.0,
+                16.0,
+                SKYBLUE,
+            );
+        }
+        draw_text(
+            &format!(
+                "{} ledger, {} messages, {} settings",
+                bindings.label(Action::OpenLedger),
+                bindings.label(Action::OpenMessages),
+                bindings.label(Action::OpenSettings),
+            ),
+            bank_x,
+            150.0,
+            16.0,
+            GRAY,
+        );
+
+        // Standing with each faction
+        for (i, faction) in system.factions.iter().enumerate() {
+            let standing = faction.standing();
+            draw_text(
+                &format!("{}: {} ({:+.0})", faction.name, standing.name(), faction.reputation),
+                bank_x,
+                180.0 + i as f32 * 18.0,
+                16.0,
+                standing.color(),
+            );
+        }
+
+        draw_time_controls(ctx);
+        ctx.ui.end_hud();
+    }
+}
+
+// Shortcuts that work anywhere in a run, from flight or while docked
+pub fn hotkeys(ctx: &mut Context) -> Transition {
+    let bindings = &ctx.settings.bindings;
+    let Some(game) = ctx.game.as_mut() else { return Transition::None };
+
+    // Time controls
+    let scale_index = TIME_SCALES.iter().position(|&scale| scale == game.time_scale).unwrap_or(0);
+    if bindings.is_pressed(Action::SlowerTime) {
+        game.time_scale = TIME_SCALES[scale_index.saturating_sub(1)];
+    }
+    if bindings.is_pressed(Action::FasterTime) {
+        game.time_scale = TIME_SCALES[(scale_index + 1).min(TIME_SCALES.len() - 1)];
+    }
+
+    if bindings.is_pressed(Action::Back) {
+        Transition::Push(Box::new(PauseMenu::new()))
+    } else if bindings.is_pressed(Action::OpenFleet) {
+        Transition::Push(Box::new(FleetScreen::new(game.fleet.active)))
+    } else if bindings.is_pressed(Action::OpenLedger) {
+        Transition::Push(Box::new(LedgerScreen::new()))
+    } else if bindings.is_pressed(Action::OpenMessages) {
+        Transition::Push(Box::new(MessageLogScreen::new()))
+    } else if bindings.is_pressed(Action::OpenSettings) {
+        Transition::Push(Box::new(SettingsScreen::new()))
+    } else {
+        Transition::None
+    }
+}
+
+// The 1x/2x/4x buttons at the top of the screen
+pub fn draw_time_controls(ctx: &mut Context) {
+    let Some(game) = ctx.game.as_mut() else { return };
+    let controls_x = ctx.ui.width() / 2.0 - 80.0;
+    for (i, &scale) in TIME_SCALES.iter().enumerate() {
+        let rect = Rect::new(controls_x + i as f32 * 50.0, 6.0, 44.0, 24.0);
+        if scale == game.time_scale {
+            draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, SKYBLUE);
+        }
+        if ctx.ui.button(rect, &format!("{}x", scale), true) {
+            game.time_scale = scale;
+        }
+    }
+    ctx.ui.tooltip(
+        Rect::new(controls_x, 6.0, TIME_SCALES.len() as f32 * 50.0, 24.0),
+        &format!(
+            "Time speed - {} slower, {} faster",
+            ctx.settings.bindings.label(Action::SlowerTime),
+            ctx.settings.bindings.label(Action::FasterTime),
+        ),
+    );
+}
+
+fn format_duration(seconds: f32) -> String {
+    let seconds = seconds.max(0.0) as i32;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
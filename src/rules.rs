@@ -0,0 +1,156 @@
+use crate::economy::{
+    ProductType, FOOD_CONSUMED_PER_INTERVAL, FOOD_CONSUMPTION_INTERVAL, FOOD_PRICE, FUEL_CONSUMPTION_PER_SECOND, FUEL_PRICE,
+    STARTING_FOOD, STARTING_FUEL, STARTING_MONEY, BUY_PRODUCT_PRICE, SELL_PRODUCT_PRICE, RARE_ORE_SELL_PRICE,
+    MEDICINE_SELL_PRICE, LUXURY_GOODS_SELL_PRICE,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Custom,
+}
+
+impl Difficulty {
+    pub fn all() -> Vec<Difficulty> {
+        vec![Difficulty::Easy, Difficulty::Normal, Difficulty::Hard, Difficulty::Custom]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Custom => "Custom",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "More money and supplies, cheap fuel, rich trade margins",
+            Difficulty::Normal => "The standard balance",
+            Difficulty::Hard => "Little money, expensive fuel, thin margins, hungry crew",
+            Difficulty::Custom => "The rules set in Settings",
+        }
+    }
+
+    pub fn next(&self) -> Difficulty {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Custom,
+            Difficulty::Custom => Difficulty::Easy,
+        }
+    }
+
+    pub fn previous(&self) -> Difficulty {
+        self.next().next().next()
+    }
+
+    pub fn parse(name: &str) -> Option<Difficulty> {
+        Difficulty::all().into_iter().find(|d| d.name() == name)
+    }
+}
+
+// What markets pay for each good before adjusting for their stock. Markets
+// sell for `margin` less than they pay, so a trader profits by hauling goods.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProductPrices {
+    pub raw_goods: i32, // Spice, minerals and biomatter
+    pub rare_ore: i32,
+    pub medicine: i32,
+    pub luxury_goods: i32,
+    pub margin: i32,
+}
+
+impl ProductPrices {
+    pub fn sell_price(&self, product: ProductType) -> i32 {
+        match product {
+            ProductType::RareOre => self.rare_ore,
+            ProductType::Medicine => self.medicine,
+            ProductType::LuxuryGoods => self.luxury_goods,
+            ProductType::Spice | ProductType::Minerals | ProductType::Biomatter => self.raw_goods,
+        }
+    }
+
+    pub fn buy_price(&self, product: ProductType) -> i32 {
+        (self.sell_price(product) - self.margin).max(1)
+    }
+
+    // Every price scaled, with the margin kept in proportion
+    fn scaled(&self, factor: f32) -> Self {
+        let scale = |price: i32| ((price as f32 * factor).round() as i32).max(1);
+        Self {
+            raw_goods: scale(self.raw_goods),
+            rare_ore: scale(self.rare_ore),
+            medicine: scale(self.medicine),
+            luxury_goods: scale(self.luxury_goods),
+            margin: scale(self.margin),
+        }
+    }
+}
+
+// The balance of a run. The economy constants are the Normal preset; the
+// other presets scale them, and Custom is whatever the player set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GameRules {
+    pub difficulty: Difficulty,
+    pub starting_money: i32,
+    pub starting_fuel: f32,
+    pub starting_food: i32,
+    pub fuel_price: i32,
+    pub food_price: i32,
+    pub fuel_consumption_per_second: f32,
+    pub food_consumption_interval: f32,
+    pub food_consumed_per_interval: i32,
+    pub prices: ProductPrices,
+}
+
+impl GameRules {
+    pub fn preset(difficulty: Difficulty) -> Self {
+        let normal = Self {
+            difficulty,
+            starting_money: STARTING_MONEY,
+            starting_fuel: STARTING_FUEL,
+            starting_food: STARTING_FOOD,
+            fuel_price: FUEL_PRICE,
+            food_price: FOOD_PRICE,
+            fuel_consumption_per_second: FUEL_CONSUMPTION_PER_SECOND,
+            food_consumption_interval: FOOD_CONSUMPTION_INTERVAL,
+            food_consumed_per_interval: FOOD_CONSUMED_PER_INTERVAL,
+            prices: ProductPrices {
+                raw_goods: SELL_PRODUCT_PRICE,
+                rare_ore: RARE_ORE_SELL_PRICE,
+                medicine: MEDICINE_SELL_PRICE,
+                luxury_goods: LUXURY_GOODS_SELL_PRICE,
+                margin: SELL_PRODUCT_PRICE - BUY_PRODUCT_PRICE,
+            },
+        };
+        match difficulty {
+            Difficulty::Normal | Difficulty::Custom => normal,
+            Difficulty::Easy => Self {
+                starting_money: STARTING_MONEY * 3,
+                starting_fuel: STARTING_FUEL + 5.0,
+                starting_food: STARTING_FOOD + 5,
+                fuel_price: FUEL_PRICE - 1,
+                food_price: FOOD_PRICE - 1,
+                fuel_consumption_per_second: FUEL_CONSUMPTION_PER_SECOND * 0.7,
+                food_consumption_interval: FOOD_CONSUMPTION_INTERVAL * 1.5,
+                prices: ProductPrices { margin: normal.prices.margin * 2, ..normal.prices.scaled(1.2) },
+                ..normal
+            },
+            Difficulty::Hard => Self {
+                starting_money: STARTING_MONEY / 2,
+                starting_fuel: STARTING_FUEL - 20.0,
+                starting_food: STARTING_FOOD - 10,
+                fuel_price: FUEL_PRICE + 2,
+                food_price: FOOD_PRICE + 1,
+                fuel_consumption_per_second: FUEL_CONSUMPTION_PER_SECOND * 1.4,
+                food_consumption_interval: FOOD_CONSUMPTION_INTERVAL * 0.8,
+                prices: ProductPrices { margin: normal.prices.margin - 1, ..normal.prices.scaled(0.85) },
+                ..normal
+            },
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use std::fs;
+use std::io;
+use macroquad::prelude::*;
+use crate::bank::Loan;
+use crate::economy::{Inventory, ProductType};
+use crate::factory::{Factory, FactoryType};
+use crate::game::Game;
+use crate::models::{Autopilot, DockId, HullStyle, MiningLaser, SmugglerCompartment, Spaceship, StarType};
+use crate::models::spaceship::SpaceshipState;
+use crate::rules::{Difficulty, GameRules};
+
+// Where the run in progress is saved, relative to the working directory
+pub const SAVE_PATH: &str = "savegame.cfg";
+
+// A run is saved as key = value lines, like the settings. The system itself
+// isn't written out: it's rebuilt from its seed and wound forward to the saved
+// time, then the markets, factions, bank and fleet are laid over it. The
+// ledger, message log, price history and any bounty hunters start afresh.
+pub fn save(game: &Game, path: &str) -> io::Result<()> {
+    let system = &game.system;
+    let rules = &game.rules;
+    let docks = system.dock_ids();
+    let dock_index = |dock: DockId| docks.iter().position(|&d| d == dock).unwrap_or(0);
+
+    let mut save = String::from("# Solar Economy saved game\n");
+    save.push_str(&format!("seed = {}\n", system.seed));
+    save.push_str(&format!("star = {}\n", system.star.star_type.name()));
+    save.push_str(&format!("planets = {}\n", system.planets.len()));
+    save.push_str(&format!("time = {}\n", game.time));
+
+    save.push_str(&format!("difficulty = {}\n", rules.difficulty.name()));
+    save.push_str(&format!("starting_money = {}\n", rules.starting_money));
+    save.push_str(&format!("starting_fuel = {}\n", rules.starting_fuel));
+    save.push_str(&format!("starting_food = {}\n", rules.starting_food));
+    save.push_str(&format!("fuel_price = {}\n", rules.fuel_price));
+    save.push_str(&format!("food_price = {}\n", rules.food_price));
+    save.push_str(&format!("fuel_consumption = {}\n", rules.fuel_consumption_per_second));
+    save.push_str(&format!("food_interval = {}\n", rules.food_consumption_interval));
+    save.push_str(&format!("food_per_interval = {}\n", rules.food_consumed_per_interval));
+    save.push_str(&format!("goods_price = {}\n", rules.prices.raw_goods));
+    save.push_str(&format!("rare_ore_price = {}\n", rules.prices.rare_ore));
+    save.push_str(&format!("medicine_price = {}\n", rules.prices.medicine));
+    save.push_str(&format!("luxury_goods_price = {}\n", rules.prices.luxury_goods));
+    save.push_str(&format!("trade_margin = {}\n", rules.prices.margin));
+
+    // Markets and factories, docks by their place in dock_ids()
+    for (i, &dock) in docks.iter().enumerate() {
+        let Some(body) = system.dockable(dock) else { continue };
+        for (product, amount) in &body.market().stock {
+            save.push_str(&format!("stock = {},{},{}\n", i, product.name(), amount));
+        }
+        for factory in &body.market().factories {
+            save.push_str(&format!(
+                "factory = {},{},{},{},{}\n",
+                i,
+                factory.kind.name(),
+                factory.progress,
+                factory.running,
+                factory.balance
+            ));
+        }
+    }
+    for (i, faction) in system.factions.iter().enumerate() {
+        save.push_str(&format!("reputation = {},{}\n", i, faction.reputation));
+    }
+
+    save.push_str(&format!("savings = {}\n", game.bank.savings));
+    for loan in &game.bank.loans {
+        save.push_str(&format!("loan = {},{},{}\n", loan.balance, loan.due_at, loan.missed_payments));
+    }
+
+    // Each ship's lines follow its name
+    save.push_str(&format!("active = {}\n", game.fleet.active));
+    for ship in &game.fleet.ships {
+        let inv = &ship.inventory;
+        save.push_str(&format!("ship = {}\n", ship.name));
+        save.push_str(&format!("position = {},{}\n", ship.position.x, ship.position.y));
+        save.push_str(&format!("hull_style = {}\n", ship.hull_style.name()));
+        save.push_str(&format!("fuel = {}\n", inv.fuel));
+        save.push_str(&format!("food = {}\n", inv.food));
+        save.push_str(&format!("money = {}\n", inv.money));
+        save.push_str(&format!("capacity = {}\n", inv.capacity));
+        for (product, amount) in &inv.cargo {
+            save.push_str(&format!("cargo = {},{}\n", product.name(), amount));
+        }
+        save.push_str(&format!("hull = {}\n", ship.hull));
+        save.push_str(&format!("insured = {}\n", ship.insured));
+        save.push_str(&format!("destroyed = {}\n", ship.destroyed));
+        if let Some(laser) = ship.mining_laser {
+            save.push_str(&format!("laser = {}\n", laser.condition));
+        }
+        if let Some(compartment) = ship.smuggler_compartment {
+            save.push_str(&format!("compartment = {}\n", compartment.capacity));
+        }
+        // Ships landing or taking off are saved as landed
+        if ship.state != SpaceshipState::Flying
+            && let Some(dock) = ship.docked_at
+        {
+            save.push_str(&format!("docked = {},{},{}\n", dock_index(dock), ship.surface_offset.x, ship.surface_offset.y));
+        }
+        if let Some(autopilot) = &ship.autopilot {
+            let stops: Vec<String> = autopilot.route.iter().map(|&dock| dock_index(dock).to_string()).collect();
+            save.push_str(&format!("route = {}\n", stops.join(",")));
+            save.push_str(&format!("next_stop = {}\n", autopilot.next_stop));
+        }
+    }
+    fs::write(path, save)
+}
+
+// None if the file is missing or doesn't describe a system and at least one ship.
+// Lines that don't parse are skipped, as in the settings.
+pub fn load(path: &str) -> Option<Game> {
+    let contents = fs::read_to_string(path).ok()?;
+    let lines: Vec<(&str, &str)> = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect();
+    let value = |key: &str| lines.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+
+    let seed = value("seed")?.parse().ok()?;
+    let star_type = StarType::parse(value("star")?)?;
+    let planet_count = value("planets")?.parse().ok()?;
+    let mut rules = GameRules::preset(value("difficulty").and_then(Difficulty::parse).unwrap_or(Difficulty::Normal));
+    for &(key, value) in &lines {
+        apply_rule(&mut rules, key, value);
+    }
+
+    let mut game = Game::generate(star_type, planet_count, rules, seed);
+    game.time = value("time").and_then(|time| time.parse().ok()).unwrap_or(0.0);
+    game.system.advance_orbits(game.time);
+    game.ledger.set_time(game.time);
+    game.messages.set_time(game.time);
+
+    let docks = game.system.dock_ids();
+    let dock_at = |index: &str| index.parse::<usize>().ok().and_then(|i| docks.get(i).copied());
+    let mut ships: Vec<Spaceship> = Vec::new();
+    for &(key, value) in &lines {
+        let fields: Vec<&str> = value.split(',').map(str::trim).collect();
+        match (key, fields.as_slice()) {
+            ("stock", [dock, product, amount]) => {
+                if let (Some(dock), Some(product), Ok(amount)) = (dock_at(dock), parse_product(product), amount.parse())
+                    && let Some(body) = game.system.dockable_mut(dock)
+                {
+                    body.market_mut().stock.insert(product, amount);
+                }
+            }
+            ("factory", [dock, kind, progress, running, balance]) => {
+                let kind = FactoryType::all().into_iter().find(|candidate| candidate.name() == *kind);
+                if let (Some(dock), Some(kind)) = (dock_at(dock), kind)
+                    && let Some(body) = game.system.dockable_mut(dock)
+                {
+                    let mut factory = Factory::new(kind);
+                    factory.progress = progress.parse().unwrap_or(0.0);
+                    factory.running = running.parse().unwrap_or(true);
+                    factory.balance = balance.parse().unwrap_or(0.0);
+                    body.market_mut().factories.push(factory);
+                }
+            }
+            ("reputation", [faction, reputation]) => {
+                if let (Ok(faction), Ok(reputation)) = (faction.parse::<usize>(), reputation.parse())
+                    && let Some(faction) = game.system.factions.get_mut(faction)
+                {
+                    faction.reputation = reputation;
+                }
+            }
+            ("savings", [savings]) => game.bank.savings = savings.parse().unwrap_or(0.0),
+            ("loan", [balance, due_at, missed]) => {
+                if let (Ok(balance), Ok(due_at), Ok(missed_payments)) = (balance.parse(), due_at.parse(), missed.parse()) {
+                    game.bank.loans.push(Loan { balance, due_at, missed_payments });
+                }
+            }
+            ("ship", _) => ships.push(Spaceship::new(value.to_string(), Vec2::ZERO, Inventory::delivered())),
+            _ => {
+                if let Some(ship) = ships.last_mut() {
+                    apply_ship(ship, key, &fields, &dock_at);
+                }
+            }
+        }
+    }
+
+    let active = value("active").and_then(|active| active.parse().ok()).unwrap_or(0);
+    if active >= ships.len() {
+        return None;
+    }
+    game.fleet.ships = ships;
+    game.fleet.active = active;
+    Some(game)
+}
+
+fn apply_ship(ship: &mut Spaceship, key: &str, fields: &[&str], dock_at: &dyn Fn(&str) -> Option<DockId>) {
+    let inv = &mut ship.inventory;
+    match (key, fields) {
+        ("position", [x, y]) => {
+            if let (Ok(x), Ok(y)) = (x.parse(), y.parse()) {
+                ship.position = vec2(x, y);
+            }
+        }
+        ("hull_style", [name]) => ship.hull_style = HullStyle::parse(name).unwrap_or(ship.hull_style),
+        ("fuel", [fuel]) => inv.fuel = fuel.parse().unwrap_or(inv.fuel),
+        ("food", [food]) => inv.food = food.parse().unwrap_or(inv.food),
+        ("money", [money]) => inv.money = money.parse().unwrap_or(inv.money),
+        ("capacity", [capacity]) => inv.capacity = capacity.parse().unwrap_or(inv.capacity),
+        ("cargo", [product, amount]) => {
+            if let (Some(product), Ok(amount)) = (parse_product(product), amount.parse()) {
+                inv.cargo.insert(product, amount);
+            }
+        }
+        ("hull", [hull]) => ship.hull = hull.parse().unwrap_or(ship.hull),
+        ("insured", [insured]) => ship.insured = insured.parse().unwrap_or(false),
+        ("destroyed", [destroyed]) => ship.destroyed = destroyed.parse().unwrap_or(false),
+        ("laser", [condition]) => {
+            if let Ok(condition) = condition.parse() {
+                ship.mining_laser = Some(MiningLaser { condition });
+            }
+        }
+        ("compartment", [capacity]) => {
+            if let Ok(capacity) = capacity.parse() {
+                ship.smuggler_compartment = Some(SmugglerCompartment { capacity });
+            }
+        }
+        ("docked", [dock, x, y]) => {
+            if let (Some(dock), Ok(x), Ok(y)) = (dock_at(dock), x.parse(), y.parse()) {
+                ship.set_down(dock, vec2(x, y));
+            }
+        }
+        ("route", stops) => {
+            let route: Vec<DockId> = stops.iter().filter_map(|stop| dock_at(stop)).collect();
+            if route.len() >= 2 {
+                ship.autopilot = Some(Autopilot::new(route));
+            }
+        }
+        ("next_stop", [next_stop]) => {
+            if let Some(autopilot) = &mut ship.autopilot {
+                autopilot.next_stop = next_stop.parse::<usize>().unwrap_or(0) % autopilot.route.len();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn apply_rule(rules: &mut GameRules, key: &str, value: &str) {
+    match key {
+        "starting_money" => rules.starting_money = value.parse().unwrap_or(rules.starting_money),
+        "starting_fuel" => rules.starting_fuel = value.parse().unwrap_or(rules.starting_fuel),
+        "starting_food" => rules.starting_food = value.parse().unwrap_or(rules.starting_food),
+        "fuel_price" => rules.fuel_price = value.parse().unwrap_or(rules.fuel_price),
+        "food_price" => rules.food_price = value.parse().unwrap_or(rules.food_price),
+        "fuel_consumption" => rules.fuel_consumption_per_second = value.parse().unwrap_or(rules.fuel_consumption_per_second),
+        "food_interval" => rules.food_consumption_interval = value.parse().unwrap_or(rules.food_consumption_interval),
+        "food_per_interval" => rules.food_consumed_per_interval = value.parse().unwrap_or(rules.food_consumed_per_interval),
+        "goods_price" => rules.prices.raw_goods = value.parse().unwrap_or(rules.prices.raw_goods),
+        "rare_ore_price" => rules.prices.rare_ore = value.parse().unwrap_or(rules.prices.rare_ore),
+        "medicine_price" => rules.prices.medicine = value.parse().unwrap_or(rules.prices.medicine),
+        "luxury_goods_price" => rules.prices.luxury_goods = value.parse().unwrap_or(rules.prices.luxury_goods),
+        "trade_margin" => rules.prices.margin = value.parse().unwrap_or(rules.prices.margin),
+        _ => {}
+    }
+}
+
+fn parse_product(name: &str) -> Option<ProductType> {
+    ProductType::all().into_iter().find(|product| product.name() == name)
+}
@@ -0,0 +1,94 @@
+use crate::game::Game;
+use crate::settings::Settings;
+use crate::ui::Ui;
+
+// Everything the scenes share: what outlives any one screen
+pub struct Context {
+    pub settings: Settings,
+    pub ui: Ui,
+    pub game: Option<Game>, // The run in progress, if any
+}
+
+// What a scene wants done to the stack after this frame
+pub enum Transition {
+    None,
+    Push(Box<dyn Scene>),
+    Pop,
+    Replace(Box<dyn Scene>), // Pop this scene and push another in its place
+    Reset(Box<dyn Scene>),   // Clear the whole stack, e.g. when quitting to the menu
+}
+
+impl Transition {
+    pub fn pop_if(close: bool) -> Self {
+        if close { Transition::Pop } else { Transition::None }
+    }
+}
+
+// A screen of the game: the menu, the flight view, an overlay on top of it.
+// Only the scene on top of the stack gets input; the ones below keep drawing
+// while overlays cover them, and keep updating unless one of them freezes them.
+pub trait Scene {
+    fn handle_input(&mut self, ctx: &mut Context) -> Transition;
+
+    // Runs every frame for each scene that isn't frozen. `focused` is true for
+    // the scene on top of the stack.
+    fn update(&mut self, _ctx: &mut Context, _focused: bool) {}
+
+    fn draw(&mut self, ctx: &mut Context);
+
+    // Overlays leave the scenes below them visible
+    fn is_overlay(&self) -> bool {
+        false
+    }
+
+    // Stops the scenes below from updating, as the pause menu does
+    fn freezes_below(&self) -> bool {
+        false
+    }
+}
+
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+}
+
+impl SceneStack {
+    pub fn new(first: Box<dyn Scene>) -> Self {
+        Self { scenes: vec![first] }
+    }
+
+    // Input, update and draw for one frame, then apply whatever the top scene asked for
+    pub fn frame(&mut self, ctx: &mut Context) {
+        let Some(top) = self.scenes.len().checked_sub(1) else { return };
+        let transition = self.scenes[top].handle_input(ctx);
+
+        // Everything from the highest freezing scene up keeps running
+        let first_running = (0..=top).rev().find(|&i| self.scenes[i].freezes_below()).unwrap_or(0);
+        for (i, scene) in self.scenes.iter_mut().enumerate().skip(first_running) {
+            scene.update(ctx, i == top);
+        }
+
+        // Draw from the highest scene that isn't an overlay, so overlays sit on what's below
+        let first_visible = self.scenes.iter().rposition(|scene| !scene.is_overlay()).unwrap_or(0);
+        for (i, scene) in self.scenes.iter_mut().enumerate().skip(first_visible) {
+            ctx.ui.interactive = i == top;
+            scene.draw(ctx);
+        }
+        ctx.ui.interactive = true;
+
+        match transition {
+            Transition::None => {}
+            Transition::Push(scene) => self.scenes.push(scene),
+            Transition::Pop => {
+                self.scenes.pop();
+            }
+            Transition::Replace(scene) => {
+                self.scenes.pop();
+                self.scenes.push(scene);
+            }
+            Transition::Reset(scene) => {
+                self.scenes.clear();
+                self.scenes.push(scene);
+            }
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use std::fs;
+use std::io;
+use crate::input::{Bindings, BINDINGS_PATH};
+use crate::models::HullStyle;
+use crate::rules::{Difficulty, GameRules};
+
+// Where settings are kept, relative to the working directory
+pub const SETTINGS_PATH: &str = "settings.cfg";
+
+// Limits for the adjustable values
+pub const MIN_GAME_SPEED: f32 = 0.5;
+pub const MAX_GAME_SPEED: f32 = 3.0;
+pub const MIN_HUD_SCALE: f32 = 0.75;
+pub const MAX_HUD_SCALE: f32 = 1.5;
+pub const MAX_STARTING_MONEY: i32 = 5000;
+pub const MAX_STARTING_FUEL: f32 = 60.0;  // Fuel and food share the cargo hold
+pub const MAX_STARTING_FOOD: i32 = 40;
+pub const MAX_SUPPLY_PRICE: i32 = 20;
+pub const MAX_PRODUCT_PRICE: i32 = 200;
+pub const MAX_TRADE_MARGIN: i32 = 20;
+pub const MAX_FUEL_CONSUMPTION: f32 = 2.0;
+pub const MIN_FOOD_INTERVAL: f32 = 1.0;
+pub const MAX_FOOD_INTERVAL: f32 = 20.0;
+
+pub struct Settings {
+    pub rules: GameRules, // What new games on Custom difficulty are played by
+    pub game_speed: f32,  // Multiplier on simulation time
+    pub hud_scale: f32,   // Size of the flight HUD, toasts and trade panels
+    pub volume: f32,      // 0 to 1
+    pub show_nebula: bool, // Nebula clouds behind the starfield
+    pub hull_style: HullStyle, // How the flagship looks in new games
+    pub show_trails: bool, // Fading trails behind planets and ships
+    pub bindings: Bindings, // Kept in their own file
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        Self {
+            rules: GameRules::preset(Difficulty::Normal),
+            game_speed: 1.0,
+            hud_scale: 1.0,
+            volume: 0.8,
+            show_nebula: true,
+            hull_style: HullStyle::Arrow,
+            show_trails: true,
+            bindings: Bindings::new(),
+        }
+    }
+
+    // Picking a preset loads its rules; Custom keeps the current ones to edit
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.rules = match difficulty {
+            Difficulty::Custom => GameRules { difficulty, ..self.rules },
+            _ => GameRules::preset(difficulty),
+        };
+    }
+
+    // The rules a new game is played by
+    pub fn rules_for(&self, difficulty: Difficulty) -> GameRules {
+        match difficulty {
+            Difficulty::Custom => GameRules { difficulty, ..self.rules },
+            _ => GameRules::preset(difficulty),
+        }
+    }
+
+    // Falls back to the defaults for a missing file and for any line that
+    // doesn't parse, so an old or hand-edited file never stops the game
+    pub fn load(path: &str) -> Self {
+        let mut settings = Settings::new();
+        if let Ok(contents) = fs::read_to_string(path) {
+            for line in contents.lines() {
+                let line = line.trim();
+                if line.starts_with('#') {
+                    continue;
+                }
+                if let Some((key, value)) = line.split_once('=') {
+                    settings.apply(key.trim(), value.trim());
+                }
+            }
+        }
+        // Only Custom keeps hand-set rules, the presets always load fresh
+        if settings.rules.difficulty != Difficulty::Custom {
+            settings.rules = GameRules::preset(settings.rules.difficulty);
+        }
+        settings.bindings = Bindings::load(BINDINGS_PATH);
+        settings
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_config())?;
+        self.bindings.save(BINDINGS_PATH)
+    }
+
+    fn to_config(&self) -> String {
+        let mut config = String::from("# Solar Economy settings\n");
+        let rules = &self.rules;
+        config.push_str(&format!("difficulty = {}\n", rules.difficulty.name()));
+        config.push_str(&format!("starting_money = {}\n", rules.starting_money));
+        config.push_str(&format!("starting_fuel = {}\n", rules.starting_fuel));
+        config.push_str(&format!("starting_food = {}\n", rules.starting_food));
+        config.push_str(&format!("fuel_price = {}\n", rules.fuel_price));
+        config.push_str(&format!("food_price = {}\n", rules.food_price));
+        config.push_str(&format!("fuel_consumption = {}\n", rules.fuel_consumption_per_second));
+        config.push_str(&format!("food_interval = {}\n", rules.food_consumption_interval));
+        config.push_str(&format!("goods_price = {}\n", rules.prices.raw_goods));
+        config.push_str(&format!("rare_ore_price = {}\n", rules.prices.rare_ore));
+        config.push_str(&format!("medicine_price = {}\n", rules.prices.medicine));
+        config.push_str(&format!("luxury_goods_price = {}\n", rules.prices.luxury_goods));
+        config.push_str(&format!("trade_margin = {}\n", rules.prices.margin));
+        config.push_str(&format!("game_speed = {}\n", self.game_speed));
+        config.push_str(&format!("hud_scale = {}\n", self.hud_scale));
+        config.push_str(&format!("volume = {}\n", self.volume));
+        config.push_str(&format!("nebula = {}\n", self.show_nebula));
+        config.push_str(&format!("hull = {}\n", self.hull_style.name()));
+        config.push_str(&format!("trails = {}\n", self.show_trails));
+        config
+    }
+
+    fn apply(&mut self, key: &str, value: &str) {
+        match key {
+            "difficulty" => {
+                if let Some(difficulty) = Difficulty::parse(value) {
+                    self.rules.difficulty = difficulty;
+                }
+            }
+            "game_speed" => {
+                if let Ok(speed) = value.parse::<f32>() {
+                    self.game_speed = speed.clamp(MIN_GAME_SPEED, MAX_GAME_SPEED);
+                }
+            }
+            "starting_money" => {
+                if let Ok(money) = value.parse::<i32>() {
+                    self.rules.starting_money = money.clamp(0, MAX_STARTING_MONEY);
+                }
+            }
+            "starting_fuel" => {
+                if let Ok(fuel) = value.parse::<f32>() {
+                    self.rules.starting_fuel = fuel.clamp(0.0, MAX_STARTING_FUEL);
+                }
+            }
+            "starting_food" => {
+                if let Ok(food) = value.parse::<i32>() {
+                    self.rules.starting_food = food.clamp(1, MAX_STARTING_FOOD);
+                }
+            }
+            "goods_price" => {
+                if let Ok(price) = value.parse::<i32>() {
+                    self.rules.prices.raw_goods = price.clamp(1, MAX_PRODUCT_PRICE);
+                }
+            }
+            "rare_ore_price" => {
+                if let Ok(price) = value.parse::<i32>() {
+                    self.rules.prices.rare_ore = price.clamp(1, MAX_PRODUCT_PRICE);
+                }
+            }
+            "medicine_price" => {
+                if let Ok(price) = value.parse::<i32>() {
+                    self.rules.prices.medicine = price.clamp(1, MAX_PRODUCT_PRICE);
+                }
+            }
+            "luxury_goods_price" => {
+                if let Ok(price) = value.parse::<i32>() {
+                    self.rules.prices.luxury_goods = price.clamp(1, MAX_PRODUCT_PRICE);
+                }
+            }
+            "trade_margin" => {
+                if let Ok(margin) = value.parse::<i32>() {
+                    self.rules.prices.margin = margin.clamp(0, MAX_TRADE_MARGIN);
+                }
+            }
+            "fuel_price" => {
+                if let Ok(price) = value.parse::<i32>() {
+                    self.rules.fuel_price = price.clamp(0, MAX_SUPPLY_PRICE);
+                }
+            }
+            "food_price" => {
+                if let Ok(price) = value.parse::<i32>() {
+                    self.rules.food_price = price.clamp(0, MAX_SUPPLY_PRICE);
+                }
+            }
+            "fuel_consumption" => {
+                if let Ok(rate) = value.parse::<f32>() {
+                    self.rules.fuel_consumption_per_second = rate.clamp(0.0, MAX_FUEL_CONSUMPTION);
+                }
+            }
+            "food_interval" => {
+                if let Ok(interval) = value.parse::<f32>() {
+                    self.rules.food_consumption_interval = interval.clamp(MIN_FOOD_INTERVAL, MAX_FOOD_INTERVAL);
+                }
+            }
+            "nebula" => {
+                if let Ok(show) = value.parse::<bool>() {
+                    self.show_nebula = show;
+                }
+            }
+            "trails" => {
+                if let Ok(show) = value.parse::<bool>() {
+                    self.show_trails = show;
+                }
+            }
+            "hull" => {
+                if let Some(style) = HullStyle::parse(value) {
+                    self.hull_style = style;
+                }
+            }
+            "hud_scale" => {
+                if let Ok(scale) = value.parse::<f32>() {
+                    self.hud_scale = scale.clamp(MIN_HUD_SCALE, MAX_HUD_SCALE);
+                }
+            }
+            "volume" => {
+                if let Ok(volume) = value.parse::<f32>() {
+                    self.volume = volume.clamp(0.0, 1.0);
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use macroquad::prelude::*;
+use crate::controls_screen::ControlsScreen;
+use crate::input::Action;
+use crate::settings::{
+    Settings, SETTINGS_PATH, MIN_GAME_SPEED, MAX_GAME_SPEED, MIN_HUD_SCALE, MAX_HUD_SCALE,
+    MAX_STARTING_MONEY, MAX_STARTING_FUEL, MAX_STARTING_FOOD, MAX_SUPPLY_PRICE, MAX_FUEL_CONSUMPTION, MIN_FOOD_INTERVAL,
+    MAX_FOOD_INTERVAL, MAX_PRODUCT_PRICE, MAX_TRADE_MARGIN,
+};
+use crate::rules::Difficulty;
+use crate::scene::{Context, Scene, Transition};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Row {
+    Difficulty,
+    GameSpeed,
+    StartingMoney,
+    StartingFuel,
+    StartingFood,
+    FuelPrice,
+    FoodPrice,
+    FuelConsumption,
+    FoodInterval,
+    GoodsPrice,
+    RareOrePrice,
+    MedicinePrice,
+    LuxuryGoodsPrice,
+    TradeMargin,
+    HudScale,
+    Volume,
+    Nebula,
+    Trails,
+    Hull,
+    Controls,
+}
+
+const ROWS: [Row; 20] = [
+    Row::Difficulty,
+    Row::StartingMoney,
+    Row::StartingFuel,
+    Row::StartingFood,
+    Row::FuelPrice,
+    Row::FoodPrice,
+    Row::FuelConsumption,
+    Row::FoodInterval,
+    Row::GoodsPrice,
+    Row::RareOrePrice,
+    Row::MedicinePrice,
+    Row::LuxuryGoodsPrice,
+    Row::TradeMargin,
+    Row::GameSpeed,
+    Row::HudScale,
+    Row::Volume,
+    Row::Nebula,
+    Row::Trails,
+    Row::Hull,
+    Row::Controls,
+];
+
+impl Row {
+    fn label(&self) -> &'static str {
+        match self {
+            Row::Difficulty => "Difficulty",
+            Row::GameSpeed => "Game speed",
+            Row::StartingMoney => "Starting money",
+            Row::StartingFuel => "Starting fuel",
+            Row::StartingFood => "Starting food",
+            Row::FuelPrice => "Fuel price",
+            Row::FoodPrice => "Food price",
+            Row::FuelConsumption => "Fuel use per second",
+            Row::FoodInterval => "Seconds per food ration",
+            Row::GoodsPrice => "Raw goods price",
+            Row::RareOrePrice => "Rare ore price",
+            Row::MedicinePrice => "Medicine price",
+            Row::LuxuryGoodsPrice => "Luxury goods price",
+            Row::TradeMargin => "Trade margin",
+            Row::HudScale => "HUD scale",
+            Row::Volume => "Volume",
+            Row::Nebula => "Nebula clouds",
+            Row::Trails => "Orbit trails",
+            Row::Hull => "Ship hull",
+            Row::Controls => "Controls",
+        }
+    }
+
+    // Rows that change the balance of new games
+    fn is_rule(&self) -> bool {
+        matches!(
+            self,
+            Row::StartingMoney
+                | Row::StartingFuel
+                | Row::StartingFood
+                | Row::FuelPrice
+                | Row::FoodPrice
+                | Row::FuelConsumption
+                | Row::FoodInterval
+                | Row::GoodsPrice
+                | Row::RareOrePrice
+                | Row::MedicinePrice
+                | Row::LuxuryGoodsPrice
+                | Row::TradeMargin
+        )
+    }
+}
+
+// Overlay for changing settings. Changes take effect immediately and are
+// written to disk when the screen closes.
+pub struct SettingsScreen {
+    selected: usize,
+    status: Option<String>,
+}
+
+impl SettingsScreen {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            status: None,
+        }
+    }
+}
+
+impl Scene for SettingsScreen {
+    fn handle_input(&mut self, ctx: &mut Context) -> Transition {
+        let settings = &mut ctx.settings;
+        let row = ROWS[self.selected];
+        let bindings = &settings.bindings;
+        if bindings.is_pressed(Action::MenuUp) && self.selected > 0 {
+            self.selected -= 1;
+        }
+        if bindings.is_pressed(Action::MenuDown) && self.selected + 1 < ROWS.len() {
+            self.selected += 1;
+        }
+        if bindings.is_pressed(Action::Confirm) && row == Row::Controls {
+            self.status = None;
+            return Transition::Push(Box::new(ControlsScreen::new()));
+        }
+        let close = bindings.is_pressed(Action::Back);
+        let step = if bindings.is_pressed(Action::MenuRight) {
+            1.0
+        } else if bindings.is_pressed(Action::MenuLeft) {
+            -1.0
+        } else {
+            0.0
+        };
+        if step != 0.0 {
+            adjust(settings, row, step);
+        }
+
+        if close {
+            if let Err(err) = settings.save(SETTINGS_PATH) {
+                self.status = Some(format!("Couldn't save settings: {}", err));
+                return Transition::None;
+            }
+            return Transition::Pop;
+        }
+        Transition::None
+    }
+
+    fn draw(&mut self, ctx: &mut Context) {
+        let settings = &ctx.settings;
+        let ui_x = screen_width() / 2.0 - 260.0;
+        let ui_y = 80.0;
+        let width = 520.0;
+        let height = 110.0 + ROWS.len() as f32 * 28.0 + 60.0;
+        draw_rectangle(ui_x, ui_y, width, height, Color::new(0.0, 0.0, 0.05, 0.95));
+        draw_rectangle_lines(ui_x, ui_y, width, height, 2.0, SKYBLUE);
+        draw_text("SETTINGS", ui_x + 20.0, ui_y + 35.0, 30.0, YELLOW);
+
+        for (i, row) in ROWS.iter().enumerate() {
+            let y = ui_y + 80.0 + i as f32 * 28.0;
+            let is_selected = i == self.selected;
+            if is_selected {
+                draw_rectangle(ui_x + 10.0, y - 18.0, width - 20.0, 26.0, Color::new(0.2, 0.3, 0.5, 0.5));
+            }
+            let color = if is_selected { WHITE } else { LIGHTGRAY };
+            draw_text(row.label(), ui_x + 20.0, y, 18.0, color);
+            let value = value_text(settings, *row);
+            let arrows = if *row == Row::Controls { value } else { format!("< {} >", value) };
+            draw_text(&arrows, ui_x + 260.0, y, 18.0, if is_selected { YELLOW } else { color });
+            if is_selected && *row == Row::Hull {
+                settings.hull_style.draw(vec2(ui_x + width - 50.0, y - 5.0), 0.0, 20.0, false);
+            }
+        }
+
+        let footer_y = ui_y + height - 20.0;
+        if let Some(status) = &self.status {
+            draw_text(status, ui_x + 20.0, footer_y - 25.0, 16.0, ORANGE);
+        } else if ROWS[self.selected] == Row::Difficulty {
+            draw_text(settings.rules.difficulty.description(), ui_x + 20.0, footer_y - 25.0, 16.0, GRAY);
+        } else if ROWS[self.selected].is_rule() {
+            draw_text("Rules apply to new games - changing one switches to Custom", ui_x + 20.0, footer_y - 25.0, 16.0, GRAY);
+        }
+        let bindings = &settings.bindings;
+        let hint = format!(
+            "{}/{} select  {}/{} change  {} controls  {} save and close",
+            bindings.label(Action::MenuUp),
+            bindings.label(Action::MenuDown),
+            bindings.label(Action::MenuLeft),
+            bindings.label(Action::MenuRight),
+            bindings.label(Action::Confirm),
+            bindings.label(Action::Back),
+        );
+        draw_text(&hint, ui_x + 20.0, footer_y, 16.0, DARKGRAY);
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
+
+fn value_text(settings: &Settings, row: Row) -> String {
+    match row {
+        Row::Difficulty => settings.rules.difficulty.name().to_string(),
+        Row::GameSpeed => format!("{:.2}x", settings.game_speed),
+        Row::StartingMoney => format!("${}", settings.rules.starting_money),
+        Row::StartingFuel => format!("{:.0}", settings.rules.starting_fuel),
+        Row::StartingFood => settings.rules.starting_food.to_string(),
+        Row::FuelPrice => format!("${}", settings.rules.fuel_price),
+        Row::FoodPrice => format!("${}", settings.rules.food_price),
+        Row::FuelConsumption => format!("{:.2}", settings.rules.fuel_consumption_per_second),
+        Row::FoodInterval => format!("{:.1}", settings.rules.food_consumption_interval),
+        Row::GoodsPrice => format!("${}", settings.rules.prices.raw_goods),
+        Row::RareOrePrice => format!("${}", settings.rules.prices.rare_ore),
+        Row::MedicinePrice => format!("${}", settings.rules.prices.medicine),
+        Row::LuxuryGoodsPrice => format!("${}", settings.rules.prices.luxury_goods),
+        Row::TradeMargin => format!("${}", settings.rules.prices.margin),
+        Row::HudScale => format!("{:.0}%", settings.hud_scale * 100.0),
+        Row::Volume => format!("{:.0}%", settings.volume * 100.0),
+        Row::Nebula => if settings.show_nebula { "On" } else { "Off" }.to_string(),
+        Row::Trails => if settings.show_trails { "On" } else { "Off" }.to_string(),
+        Row::Hull => settings.hull_style.name().to_string(),
+        Row::Controls => format!("{} to rebind keys", settings.bindings.label(Action::Confirm)),
+    }
+}
+
+fn adjust(settings: &mut Settings, row: Row, step: f32) {
+    if row.is_rule() {
+        settings.rules.difficulty = Difficulty::Custom;
+    }
+    let rules = &mut settings.rules;
+    match row {
+        Row::Difficulty => {
+            let difficulty = if step > 0.0 { rules.difficulty.next() } else { rules.difficulty.previous() };
+            settings.set_difficulty(difficulty);
+        }
+        Row::StartingMoney => rules.starting_money = (rules.starting_money + step as i32 * 50).clamp(0, MAX_STARTING_MONEY),
+        Row::StartingFuel => rules.starting_fuel = (rules.starting_fuel + step * 5.0).clamp(0.0, MAX_STARTING_FUEL),
+        Row::StartingFood => rules.starting_food = (rules.starting_food + step as i32 * 5).clamp(1, MAX_STARTING_FOOD),
+        Row::FuelPrice => rules.fuel_price = (rules.fuel_price + step as i32).clamp(0, MAX_SUPPLY_PRICE),
+        Row::FoodPrice => rules.food_price = (rules.food_price + step as i32).clamp(0, MAX_SUPPLY_PRICE),
+        Row::FuelConsumption => {
+            rules.fuel_consumption_per_second = (rules.fuel_consumption_per_second + step * 0.05).clamp(0.0, MAX_FUEL_CONSUMPTION);
+        }
+        Row::FoodInterval => {
+            rules.food_consumption_interval = (rules.food_consumption_interval + step * 0.5).clamp(MIN_FOOD_INTERVAL, MAX_FOOD_INTERVAL);
+        }
+        Row::GoodsPrice => rules.prices.raw_goods = (rules.prices.raw_goods + step as i32).clamp(1, MAX_PRODUCT_PRICE),
+        Row::RareOrePrice => rules.prices.rare_ore = (rules.prices.rare_ore + step as i32 * 5).clamp(1, MAX_PRODUCT_PRICE),
+        Row::MedicinePrice => rules.prices.medicine = (rules.prices.medicine + step as i32 * 5).clamp(1, MAX_PRODUCT_PRICE),
+        Row::LuxuryGoodsPrice => rules.prices.luxury_goods = (rules.prices.luxury_goods + step as i32 * 5).clamp(1, MAX_PRODUCT_PRICE),
+        Row::TradeMargin => rules.prices.margin = (rules.prices.margin + step as i32).clamp(0, MAX_TRADE_MARGIN),
+        Row::GameSpeed => settings.game_speed = (settings.game_speed + step * 0.25).clamp(MIN_GAME_SPEED, MAX_GAME_SPEED),
+        Row::HudScale => settings.hud_scale = (settings.hud_scale + step * 0.05).clamp(MIN_HUD_SCALE, MAX_HUD_SCALE),
+        Row::Volume => settings.volume = (settings.volume + step * 0.1).clamp(0.0, 1.0),
+        Row::Nebula => settings.show_nebula = !settings.show_nebula,
+        Row::Trails => settings.show_trails = !settings.show_trails,
+        Row::Hull => settings.hull_style = if step > 0.0 { settings.hull_style.next() } else { settings.hull_style.previous() },
+        _ => {}
+    }
+}
@@ -0,0 +1,542 @@
+use macroquad::prelude::*;
+use crate::charts;
+use crate::economy::{Inventory, ProductType, TradeService, TradeSide, TradeTerms, REPUTATION_PER_TRADE_UNIT};
+use crate::economy::{MINING_LASER_PRICE,
+    HULL_REPAIR_PRICE, INSURANCE_PRICE, INSURANCE_PAYOUT, MAX_HULL, LOAN_AMOUNT, DEPOSIT_AMOUNT, SHIP_PRICE,
+    FACTORY_PRICE, SMUGGLER_COMPARTMENT_PRICE, FUEL_BUY_AMOUNT, MAX_PRODUCT_BUY_QUANTITY};
+use crate::factory::{Factory, FactoryType};
+use crate::game::Game;
+use crate::input::{Action, Bindings};
+use crate::ledger::{Transaction, TransactionKind};
+use crate::models::{DockId, MiningLaser, SmugglerCompartment, SolarSystem, Spaceship};
+use crate::playing_screen::{draw_time_controls, hotkeys};
+use crate::scene::{Context, Scene, Transition};
+use crate::ui::Ui;
+
+// The market, bank and factory panels shown while the active ship is docked.
+// Buttons clicked while drawing are carried out with the key presses on the next frame.
+pub struct TradeScreen {
+    selected_product: usize, // Which of the market's goods [1] buys
+    buy_quantity: i32,       // How many units of it [1] buys
+    show_market_info: bool,
+    chart_product: usize,    // Index into ProductType::all() for the price chart
+    clicked: Vec<Action>,
+}
+
+impl TradeScreen {
+    pub fn new() -> Self {
+        Self {
+            selected_product: 0,
+            buy_quantity: 1,
+            show_market_info: false,
+            chart_product: 0,
+            clicked: Vec::new(),
+        }
+    }
+
+    fn trade(&mut self, game: &mut Game, dock: DockId, bindings: &Bindings, clicked: &[Action]) {
+        let triggered = |action: Action| clicked.contains(&action) || bindings.is_pressed(action);
+        if triggered(Action::MarketInfo) {
+            self.show_market_info = !self.show_market_info;
+        }
+
+        // Market info only pages through the charts
+        if self.show_market_info {
+            let count = ProductType::all().len();
+            if bindings.is_pressed(Action::MenuRight) {
+                self.chart_product = (self.chart_product + 1) % count;
+            }
+            if bindings.is_pressed(Action::MenuLeft) {
+                self.chart_product = (self.chart_product + count - 1) % count;
+            }
+            return;
+        }
+        if bindings.is_pressed(Action::MenuRight) {
+            self.buy_quantity = (self.buy_quantity + 1).min(MAX_PRODUCT_BUY_QUANTITY);
+        }
+        if bindings.is_pressed(Action::MenuLeft) {
+            self.buy_quantity = (self.buy_quantity - 1).max(1);
+        }
+
+        let Game { system, fleet, ledger, messages, rules, .. } = game;
+        let terms: Vec<(ProductType, TradeTerms)> = ProductType::all()
+            .into_iter()
+            .map(|p| (p, system.trade_terms(dock, p, TradeSide::Buy)))
+            .collect();
+        let terms_for = |product: ProductType| terms.iter().find(|(p, _)| *p == product).map(|(_, t)| *t).unwrap_or(TradeTerms::standard());
+        let Some(body) = system.dockable_mut(dock) else { return };
+        let location = body.name().to_string();
+        let mut units_traded = 0;
+
+        if triggered(Action::NextProduct) {
+            self.selected_product += 1;
+        }
+        let for_sale = body.market().for_sale();
+        let selected = for_sale.get(self.selected_product % for_sale.len().max(1)).copied();
+
+        let ship = fleet.active_mut();
+        if triggered(Action::BuyProduct)
+            && let Some(product) = selected
+        {
+            // Buy the selected local good
+            let mut trade = TradeService::new(body.market_mut(), &mut ship.inventory, rules);
+            match trade.buy(product, self.buy_quantity, terms_for(product)) {
+                Ok(quote) => {
+                    units_traded += quote.quantity;
+                    ledger.record_trade(&ship.name, &location, &quote);
+                }
+                Err(err) => messages.error(err.describe()),
+            }
+        }
+        if triggered(Action::BuyFuel) {
+            // Buy fuel
+            match TradeService::new(body.market_mut(), &mut ship.inventory, rules).buy_fuel() {
+                Ok(cost) => ledger.record(Transaction::new(TransactionKind::Fuel, &ship.name, &location, -cost)),
+                Err(err) => messages.error(err.describe()),
+            }
+        }
+        if triggered(Action::BuyFood) {
+            // Buy food
+            match TradeService::new(body.market_mut(), &mut ship.inventory, rules).buy_food() {
+                Ok(cost) => ledger.record(Transaction::new(TransactionKind::Food, &ship.name, &location, -cost)),
+                Err(err) => messages.error(err.describe()),
+            }
+        }
+        if triggered(Action::BuyLaser) {
+            // Buy or replace the mining laser
+            let price = mining_laser_price(ship.mining_laser);
+            if price > 0 {
+                match ship.inventory.spend(price) {
+                    Ok(()) => {
+                        ship.mining_laser = Some(MiningLaser::new());
+                        ledger.record(Transaction::new(TransactionKind::Equipment, &ship.name, &location, -price));
+                    }
+                    Err(err) => messages.error(err.describe()),
+                }
+            }
+        }
+        if triggered(Action::RepairHull) {
+            // Repair as much of the hull as we can afford, at least one point
+            let affordable = (ship.inventory.money / HULL_REPAIR_PRICE).max(1) as f32;
+            let points = (MAX_HULL - ship.hull).ceil().min(affordable);
+            if points > 0.0 {
+                let cost = points as i32 * HULL_REPAIR_PRICE;
+                match ship.inventory.spend(cost) {
+                    Ok(()) => {
+                        ship.repair(points);
+                        ledger.record(Transaction::new(TransactionKind::Repair, &ship.name, &location, -cost));
+                    }
+                    Err(err) => messages.error(err.describe()),
+                }
+            }
+        }
+        if triggered(Action::BuyInsurance) && !ship.insured {
+            match ship.inventory.spend(INSURANCE_PRICE) {
+                Ok(()) => {
+                    ship.insured = true;
+                    ledger.record(Transaction::new(TransactionKind::Insurance, &ship.name, &location, -INSURANCE_PRICE));
+                }
+                Err(err) => messages.error(err.describe()),
+            }
+        }
+        if triggered(Action::BuyCompartment) && ship.smuggler_compartment.is_none() {
+            match ship.inventory.spend(SMUGGLER_COMPARTMENT_PRICE) {
+                Ok(()) => {
+                    ship.smuggler_compartment = Some(SmugglerCompartment::new());
+                    ledger.record(Transaction::new(TransactionKind::Equipment, &ship.name, &location, -SMUGGLER_COMPARTMENT_PRICE));
+                }
+                Err(err) => messages.error(err.describe()),
+            }
+        }
+        if triggered(Action::BuyShip) {
+            match ship.inventory.spend(SHIP_PRICE) {
+                Ok(()) => {
+                    // New ships wait at this dock until given a route
+                    ledger.record(Transaction::new(TransactionKind::Ship, &ship.name, &location, -SHIP_PRICE));
+                    let mut new_ship = Spaceship::new(fleet.next_ship_name(), fleet.active().position, Inventory::delivered());
+                    new_ship.hull_style = fleet.active().hull_style;
+                    new_ship.dock_beside(fleet.active());
+                    fleet.add(new_ship);
+                    messages.success(format!("New ship purchased - press {} to assign it a route", bindings.label(Action::OpenFleet)));
+                }
+                Err(err) => messages.error(err.describe()),
+            }
+        }
+
+        system.adjust_reputation(dock, units_traded as f32 * REPUTATION_PER_TRADE_UNIT);
+    }
+
+    // Banks only operate on planets
+    fn bank(game: &mut Game, planet: usize, bindings: &Bindings) {
+        let ship = game.fleet.active_mut();
+        let money_before = ship.inventory.money;
+        let mut results = Vec::new();
+        if bindings.is_pressed(Action::Borrow) {
+            results.push(game.bank.borrow(&mut ship.inventory, LOAN_AMOUNT, game.time));
+        }
+        if bindings.is_pressed(Action::Repay) {
+            results.push(game.bank.repay(&mut ship.inventory, LOAN_AMOUNT).map(|_| ()));
+        }
+        if bindings.is_pressed(Action::Deposit) {
+            results.push(game.bank.deposit(&mut ship.inventory, DEPOSIT_AMOUNT));
+        }
+        if bindings.is_pressed(Action::Withdraw) {
+            results.push(game.bank.withdraw(&mut ship.inventory, DEPOSIT_AMOUNT));
+        }
+        for err in results.into_iter().filter_map(Result::err) {
+            game.messages.error(err.describe());
+        }
+        let amount = ship.inventory.money - money_before;
+        game.ledger.record(Transaction::new(TransactionKind::Bank, &ship.name, &game.system.planets[planet].name, amount));
+    }
+
+    // Player-owned factories, also planet-only
+    fn factories(game: &mut Game, planet: usize, bindings: &Bindings) {
+        let Some(planet) = game.system.planets.get_mut(planet) else { return };
+        let factories = &mut planet.market.factories;
+        let ship = game.fleet.active_mut();
+
+        // Build whichever factory types this planet doesn't have yet
+        let actions = [Action::BuildFirstFactory, Action::BuildSecondFactory];
+        for (kind, action) in FactoryType::all().into_iter().zip(actions) {
+            if factories.iter().any(|f| f.kind == kind) {
+                continue;
+            }
+            if !bindings.is_pressed(action) {
+                continue;
+            }
+            match ship.inventory.spend(FACTORY_PRICE) {
+                Ok(()) => {
+                    factories.push(Factory::new(kind));
+                    game.ledger.record(Transaction::new(TransactionKind::Factory, &ship.name, &planet.name, -FACTORY_PRICE));
+                }
+                Err(err) => game.messages.error(err.describe()),
+            }
+        }
+        if bindings.is_pressed(Action::CollectFactories) {
+            for factory in factories.iter_mut() {
+                let amount = factory.collect(&mut ship.inventory.money);
+                game.ledger.record(Transaction::new(TransactionKind::Factory, &ship.name, &planet.name, amount));
+            }
+        }
+    }
+
+    fn draw_trade(&mut self, ui: &mut Ui, bindings: &Bindings, game: &Game, dock: DockId) {
+        let system = &game.system;
+        let ship = game.fleet.active();
+        let Some(body) = system.dockable(dock) else { return };
+
+        // The local faction's prices and attitude
+        let contraband: Vec<&str> = ProductType::all()
+            .into_iter()
+            .filter(|&p| system.is_contraband(dock, p))
+            .map(|p| p.name())
+            .collect();
+        let terms_for = |product: ProductType| system.trade_terms(dock, product, TradeSide::Buy);
+        let (faction_text, faction_color) = system
+            .faction_of(dock)
+            .map(|faction| {
+                let tariffs = match faction.tariff_factor() {
+                    0.0 => "Tariffs waived".to_string(),
+                    factor => format!("Tariffs x{:.1}", factor),
+                };
+                (
+                    format!("{} - {} ({:+.0})  {}", faction.name, faction.standing().name(), faction.reputation, tariffs),
+                    faction.standing().color(),
+                )
+            })
+            .unwrap_or(("Independent - no tariffs".to_string(), LIGHTGRAY));
+
+        // Trading UI
+        let ui_x = ui.width() / 2.0 - 200.0;
+        let ui_y = ui.height() / 2.0 - 150.0;
+
+        // Background
+        draw_rectangle(ui_x - 10.0, ui_y - 10.0, 420.0, 405.0, Color::new(0.0, 0.0, 0.0, 0.8));
+
+        // Title
+        let title = match body.market().produces {
+            Some(product) => format!("Trading at {} - Produces: {}", body.name(), product.name()),
+            None => format!("Trading at {} - Trade Hub", body.name()),
+        };
+        draw_text(&title, ui_x, ui_y + 20.0, 20.0, YELLOW);
+        draw_text(&faction_text, ui_x, ui_y + 42.0, 16.0, faction_color);
+
+        // Available items to buy, each a button that does the same as its key
+        let row = |y_offset: f32| Rect::new(ui_x - 4.0, ui_y + y_offset - 17.0, 330.0, 23.0);
+        let mut y_offset = 70.0;
+        draw_text("Click or press keys to buy:", ui_x, ui_y + y_offset, 18.0, WHITE);
+        if !contraband.is_empty() {
+            draw_text(&format!("Contraband: {}", contraband.join(", ")), ui_x + 160.0, ui_y + y_offset, 16.0, ORANGE);
+        }
+        y_offset += 30.0;
+
+        let for_sale = body.market().for_sale();
+        let selected = for_sale.get(self.selected_product % for_sale.len().max(1)).copied();
+        if let Some(product) = selected {
+            let stock = body.market().stock_of(product);
+            // Priced for the whole quantity, since each unit bought raises the next one's price
+            let quote = body.market().quote_trade(TradeSide::Buy, product, self.buy_quantity, terms_for(product));
+            let levies = quote.tariff + quote.tax;
+            let price = if levies > 0 {
+                format!("${} + ${} levies", quote.goods, levies)
+            } else {
+                format!("${}", quote.goods)
+            };
+            let label = format!("[{}] Buy {} x{} - {} ({} in stock)", bindings.label(Action::BuyProduct), product.name(), self.buy_quantity, price, stock);
+            if ui.button(row(y_offset), &label, true) {
+                self.clicked.push(Action::BuyProduct);
+            }
+            ui.tooltip(
+                row(y_offset),
+                &format!("For {} units: ${} goods, ${} tariff, ${} sales tax", quote.quantity, quote.goods, quote.tariff, quote.tax),
+            );
+            if for_sale.len() > 1
+                && ui.button(Rect::new(ui_x + 336.0, ui_y + y_offset - 17.0, 64.0, 23.0), &format!("[{}] next", bindings.label(Action::NextProduct)), true)
+            {
+                self.clicked.push(Action::NextProduct);
+            }
+            y_offset += 25.0;
+            draw_text(&format!("Quantity: {}", self.buy_quantity), ui_x, ui_y + y_offset, 16.0, LIGHTGRAY);
+            let quantity = ui.slider(
+                Rect::new(ui_x + 100.0, ui_y + y_offset - 14.0, 200.0, 18.0),
+                self.buy_quantity as f32,
+                1.0,
+                MAX_PRODUCT_BUY_QUANTITY as f32,
+            );
+            self.buy_quantity = quantity.round() as i32;
+            let keys = format!("{}/{}", bindings.label(Action::MenuLeft), bindings.label(Action::MenuRight));
+            draw_text(&keys, ui_x + 310.0, ui_y + y_offset, 14.0, GRAY);
+        } else {
+            ui.button(row(y_offset), &format!("[{}] Nothing produced here", bindings.label(Action::BuyProduct)), false);
+        }
+        y_offset += 25.0;
+        if ui.button(row(y_offset), &format!("[{}] Buy Fuel - ${}ea", bindings.label(Action::BuyFuel), game.rules.fuel_price), true) {
+            self.clicked.push(Action::BuyFuel);
+        }
+        ui.tooltip(row(y_offset), &format!("{} units of fuel per purchase", FUEL_BUY_AMOUNT));
+        y_offset += 25.0;
+        if ui.button(row(y_offset), &format!("[{}] Buy Food - ${}ea", bindings.label(Action::BuyFood), game.rules.food_price), true) {
+            self.clicked.push(Action::BuyFood);
+        }
+        y_offset += 25.0;
+
+        // A worn laser can be swapped for a new one at a discount
+        let laser_price = mining_laser_price(ship.mining_laser);
+        let label = if laser_price > 0 {
+            format!("[{}] Buy Mining Laser - ${}", bindings.label(Action::BuyLaser), laser_price)
+        } else {
+            format!("[{}] Mining Laser - in perfect condition", bindings.label(Action::BuyLaser))
+        };
+        if ui.button(row(y_offset), &label, laser_price > 0) {
+            self.clicked.push(Action::BuyLaser);
+        }
+        y_offset += 25.0;
+
+        let repair_cost = hull_repair_cost(ship);
+        let label = if repair_cost > 0 {
+            format!("[{}] Repair Hull - ${}", bindings.label(Action::RepairHull), repair_cost)
+        } else {
+            format!("[{}] Hull needs no repairs", bindings.label(Action::RepairHull))
+        };
+        if ui.button(row(y_offset), &label, repair_cost > 0) {
+            self.clicked.push(Action::RepairHull);
+        }
+        y_offset += 25.0;
+
+        let label = if ship.insured {
+            format!("[{}] Insured - policy active", bindings.label(Action::BuyInsurance))
+        } else {
+            format!("[{}] Buy Insurance - ${} (pays ${})", bindings.label(Action::BuyInsurance), INSURANCE_PRICE, INSURANCE_PAYOUT)
+        };
+        if ui.button(row(y_offset), &label, !ship.insured) {
+            self.clicked.push(Action::BuyInsurance);
+        }
+        ui.tooltip(row(y_offset), &format!("Pays ${} if this ship is destroyed", INSURANCE_PAYOUT));
+        y_offset += 25.0;
+        if ui.button(row(y_offset), &format!("[{}] Buy Trade Ship - ${}", bindings.label(Action::BuyShip), SHIP_PRICE), true) {
+            self.clicked.push(Action::BuyShip);
+        }
+        ui.tooltip(row(y_offset), "Another ship that can fly a trade route on its own");
+        y_offset += 25.0;
+        let label = match ship.smuggler_compartment {
+            Some(compartment) => {
+                format!("[{}] Smuggler's compartment fitted - hides {} units", bindings.label(Action::BuyCompartment), compartment.capacity)
+            }
+            None => format!("[{}] Buy Smuggler's Compartment - ${}", bindings.label(Action::BuyCompartment), SMUGGLER_COMPARTMENT_PRICE),
+        };
+        if ui.button(row(y_offset), &label, ship.smuggler_compartment.is_none()) {
+            self.clicked.push(Action::BuyCompartment);
+        }
+        ui.tooltip(row(y_offset), "Hides contraband from customs scans");
+        y_offset += 35.0;
+
+        draw_text(&format!("Available space: {}", ship.inventory.available_space()), ui_x, ui_y + y_offset, 16.0, GRAY);
+        let taxes = &body.market().taxes;
+        draw_text(
+            &format!("Docking fee ${}  Sales tax {:.0}%", system.docking_fee(dock), taxes.sales_tax * 100.0),
+            ui_x + 170.0,
+            ui_y + y_offset,
+            16.0,
+            GRAY,
+        );
+        y_offset += 25.0;
+        if ui.button(Rect::new(ui_x - 4.0, ui_y + y_offset - 17.0, 160.0, 23.0), &format!("[{}] Take off", bindings.label(Action::TakeOff)), true) {
+            self.clicked.push(Action::TakeOff);
+        }
+        let info_label = format!("[{}] Market info", bindings.label(Action::MarketInfo));
+        if ui.button(Rect::new(ui_x + 286.0, ui_y + y_offset - 17.0, 114.0, 23.0), &info_label, !self.show_market_info) {
+            self.clicked.push(Action::MarketInfo);
+        }
+        y_offset += 22.0;
+
+        // Import tariffs decide where the hold is worth selling, so list them all
+        let mut imports: Vec<String> = ProductType::all()
+            .into_iter()
+            .filter_map(|p| {
+                let rate = taxes.import_tariff(p) * terms_for(p).tariff_factor;
+                (rate > 0.0).then(|| format!("{} {:.0}%", p.name(), rate * 100.0))
+            })
+            .collect();
+        if imports.is_empty() {
+            imports.push("none".to_string());
+        }
+        draw_text(&format!("Import tariffs: {}", imports.join(", ")), ui_x, ui_y + y_offset, 14.0, GRAY);
+
+        // Market info replaces the trade list with charts
+        if self.show_market_info {
+            let products = ProductType::all();
+            let product = products[self.chart_product % products.len()];
+
+            // The panel grows with the index so every planet's row fits above the button
+            let index_y = ui_y + 215.0;
+            let back_y = index_y + charts::price_index_height(system) + 8.0;
+            draw_rectangle(ui_x - 10.0, ui_y + 30.0, 420.0, back_y + 27.0 - (ui_y + 30.0), Color::new(0.0, 0.0, 0.05, 1.0));
+            let paging = format!("< {} >  ({}/{})", product.name(), bindings.label(Action::MenuLeft), bindings.label(Action::MenuRight));
+            draw_text(&paging, ui_x, ui_y + 50.0, 18.0, WHITE);
+            charts::draw_price_chart(body.market().history.get(&product), Rect::new(ui_x, ui_y + 60.0, 400.0, 110.0));
+            charts::draw_price_index(system, product, ui_x, index_y, 400.0);
+            let back_label = format!("[{}] Back to trading", bindings.label(Action::MarketInfo));
+            if ui.button(Rect::new(ui_x - 4.0, back_y, 160.0, 23.0), &back_label, true) {
+                self.show_market_info = false;
+            }
+        }
+    }
+
+    fn draw_bank(ui: &Ui, bindings: &Bindings, game: &Game) {
+        let inv = &game.fleet.active().inventory;
+        let bank = &game.bank;
+        let ui_x = ui.width() / 2.0 + 230.0;
+        let ui_y = ui.height() / 2.0 - 150.0;
+        draw_rectangle(ui_x - 10.0, ui_y - 10.0, 260.0, 230.0, Color::new(0.0, 0.0, 0.0, 0.8));
+        draw_text("Planetary Bank", ui_x, ui_y + 20.0, 20.0, YELLOW);
+
+        let mut y_offset = 50.0;
+        draw_text(&format!("Debt: ${}", bank.total_debt()), ui_x, ui_y + y_offset, 16.0, WHITE);
+        y_offset += 20.0;
+        draw_text(&format!("Savings: ${}", bank.savings.floor()), ui_x, ui_y + y_offset, 16.0, WHITE);
+        y_offset += 20.0;
+        draw_text(&format!("Net worth: ${}", bank.net_worth(inv)), ui_x, ui_y + y_offset, 16.0, WHITE);
+        y_offset += 20.0;
+        draw_text(&format!("Credit available: ${}", bank.available_credit(inv)), ui_x, ui_y + y_offset, 16.0, GRAY);
+        y_offset += 30.0;
+
+        draw_text(&format!("[{}] Borrow ${}", bindings.label(Action::Borrow), LOAN_AMOUNT), ui_x, ui_y + y_offset, 16.0, GREEN);
+        y_offset += 20.0;
+        draw_text(&format!("[{}] Repay ${}", bindings.label(Action::Repay), LOAN_AMOUNT), ui_x, ui_y + y_offset, 16.0, GREEN);
+        y_offset += 20.0;
+        draw_text(&format!("[{}] Deposit ${}", bindings.label(Action::Deposit), DEPOSIT_AMOUNT), ui_x, ui_y + y_offset, 16.0, GREEN);
+        y_offset += 20.0;
+        draw_text(&format!("[{}] Withdraw ${}", bindings.label(Action::Withdraw), DEPOSIT_AMOUNT), ui_x, ui_y + y_offset, 16.0, GREEN);
+    }
+
+    fn draw_factories(ui: &Ui, bindings: &Bindings, system: &SolarSystem, planet: usize) {
+        let Some(planet) = system.planets.get(planet) else { return };
+        let ui_x = ui.width() / 2.0 + 230.0;
+        let ui_y = ui.height() / 2.0 + 90.0;
+        let factories = &planet.market.factories;
+        let panel_height = 110.0 + factories.len() as f32 * 40.0;
+        draw_rectangle(ui_x - 10.0, ui_y - 10.0, 260.0, panel_height, Color::new(0.0, 0.0, 0.0, 0.8));
+        draw_text("Factories", ui_x, ui_y + 10.0, 20.0, YELLOW);
+
+        let mut y_offset = 35.0;
+        for factory in factories {
+            let status = if factory.running {
+                format!("{:.0}%", factory.progress / factory.kind.cycle_time() * 100.0)
+            } else {
+                "waiting for inputs".to_string()
+            };
+            draw_text(&format!("{} - {}", factory.kind.name(), status), ui_x, ui_y + y_offset, 16.0, WHITE);
+            let balance_color = if factory.balance >= 0.0 { GREEN } else { RED };
+            draw_text(&format!("  {}  (${:.0})", factory.kind.recipe(), factory.balance), ui_x, ui_y + y_offset + 18.0, 14.0, balance_color);
+            y_offset += 40.0;
+        }
+
+        // Offer to build whichever factory types this planet doesn't have yet
+        let actions = [Action::BuildFirstFactory, Action::BuildSecondFactory];
+        for (kind, action) in FactoryType::all().into_iter().zip(actions) {
+            if factories.iter().any(|f| f.kind == kind) {
+                continue;
+            }
+            draw_text(&format!("[{}] Build {} - ${}", bindings.label(action), kind.name(), FACTORY_PRICE), ui_x, ui_y + y_offset, 16.0, GREEN);
+            y_offset += 20.0;
+        }
+        if !factories.is_empty() {
+            draw_text(&format!("[{}] Collect earnings / pay upkeep", bindings.label(Action::CollectFactories)), ui_x, ui_y + y_offset, 16.0, GREEN);
+        }
+    }
+}
+
+impl Scene for TradeScreen {
+    fn handle_input(&mut self, ctx: &mut Context) -> Transition {
+        let clicked = std::mem::take(&mut self.clicked);
+        let bindings = &ctx.settings.bindings;
+        let Some(game) = ctx.game.as_mut() else { return Transition::Pop };
+        let Some(dock) = game.fleet.active().docked_at.filter(|_| game.is_docked()) else { return Transition::Pop };
+        if game.game_over_reason().is_some() {
+            return Transition::Pop;
+        }
+        if clicked.contains(&Action::TakeOff) || bindings.is_pressed(Action::TakeOff) {
+            game.fleet.active_mut().begin_takeoff(&game.system);
+            return Transition::Pop;
+        }
+
+        self.trade(game, dock, bindings, &clicked);
+        if let DockId::Planet(planet) = dock {
+            Self::bank(game, planet, bindings);
+            Self::factories(game, planet, bindings);
+        }
+        hotkeys(ctx)
+    }
+
+    fn draw(&mut self, ctx: &mut Context) {
+        let Context { settings, ui, game } = ctx;
+        let Some(game) = game.as_ref() else { return };
+        let Some(dock) = game.fleet.active().docked_at else { return };
+        ui.begin_hud(settings.hud_scale);
+        self.draw_trade(ui, &settings.bindings, game, dock);
+        if let DockId::Planet(planet) = dock {
+            Self::draw_bank(ui, &settings.bindings, game);
+            Self::draw_factories(ui, &settings.bindings, &game.system, planet);
+        }
+        // The flight view's time buttons are under this overlay, so they're drawn again to take clicks
+        draw_time_controls(ctx);
+        ctx.ui.end_hud();
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
+
+// Price of a fresh mining laser, with a trade-in discount for the current one
+fn mining_laser_price(current: Option<MiningLaser>) -> i32 {
+    match current {
+        Some(laser) => (MINING_LASER_PRICE as f32 * (1.0 - laser.condition)).round() as i32,
+        None => MINING_LASER_PRICE,
+    }
+}
+
+fn hull_repair_cost(ship: &Spaceship) -> i32 {
+    (MAX_HULL - ship.hull).ceil() as i32 * HULL_REPAIR_PRICE
+}
@@ -0,0 +1,157 @@
+use macroquad::prelude::*;
+
+const HOVER_COLOR: Color = Color::new(0.2, 0.3, 0.5, 0.5);
+const PRESSED_COLOR: Color = Color::new(0.3, 0.45, 0.7, 0.7);
+const TOOLTIP_DELAY: f32 = 0.4; // Seconds the mouse rests on a widget before its tooltip shows
+
+// What a list did this frame
+pub struct ListResponse {
+    pub hovered: Option<usize>,
+    pub clicked: Option<usize>,
+}
+
+// Immediate-mode widgets drawn with macroquad. Each widget draws itself and
+// reports what the mouse did to it in the same call; there is no retained
+// widget state beyond the tooltip, which is drawn last so it sits on top.
+pub struct Ui {
+    pub interactive: bool, // False while an overlay covers the widgets
+    tooltip: Option<String>,
+    hover_time: f32,
+    last_mouse: Vec2,
+    scale: f32, // Zoom of the HUD space between begin_hud and end_hud
+}
+
+impl Ui {
+    pub fn new() -> Self {
+        Self {
+            interactive: true,
+            tooltip: None,
+            hover_time: 0.0,
+            last_mouse: Vec2::ZERO,
+            scale: 1.0,
+        }
+    }
+
+    // Call once at the start of every frame
+    pub fn begin_frame(&mut self) {
+        let mouse = Vec2::from(mouse_position());
+        if mouse == self.last_mouse {
+            self.hover_time += get_frame_time();
+        } else {
+            self.hover_time = 0.0;
+        }
+        self.last_mouse = mouse;
+        self.tooltip = None;
+        self.interactive = true;
+    }
+
+    // Draws everything up to end_hud zoomed by the scale, so text, panels
+    // and the mouse hit areas of the widgets all grow together
+    pub fn begin_hud(&mut self, scale: f32) {
+        self.scale = scale;
+        set_camera(&Camera2D::from_display_rect(Rect::new(0.0, 0.0, self.width(), self.height())));
+    }
+
+    pub fn end_hud(&mut self) {
+        self.scale = 1.0;
+        set_default_camera();
+    }
+
+    // Size of the screen in the current drawing space
+    pub fn width(&self) -> f32 {
+        screen_width() / self.scale
+    }
+
+    pub fn height(&self) -> f32 {
+        screen_height() / self.scale
+    }
+
+    fn mouse(&self) -> Vec2 {
+        Vec2::from(mouse_position()) / self.scale
+    }
+
+    // Keyboard navigation wins unless the mouse is actually being moved
+    pub fn mouse_moved(&self) -> bool {
+        self.hover_time == 0.0
+    }
+
+    pub fn hovered(&self, rect: Rect) -> bool {
+        self.interactive && rect.contains(self.mouse())
+    }
+
+    // A text button; disabled buttons are drawn grey and never click
+    pub fn button(&mut self, rect: Rect, label: &str, enabled: bool) -> bool {
+        let hovered = enabled && self.hovered(rect);
+        if hovered {
+            let color = if is_mouse_button_down(MouseButton::Left) { PRESSED_COLOR } else { HOVER_COLOR };
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, color);
+        }
+        let text_size = (rect.h - 4.0).min(18.0);
+        let color = if !enabled {
+            GRAY
+        } else if hovered {
+            WHITE
+        } else {
+            GREEN
+        };
+        draw_text(label, rect.x + 4.0, rect.y + rect.h / 2.0 + text_size / 3.0, text_size, color);
+        hovered && is_mouse_button_pressed(MouseButton::Left)
+    }
+
+    // A column of rows, one per item. The selected row is highlighted the
+    // same way keyboard navigation highlights it.
+    pub fn list(&mut self, x: f32, y: f32, width: f32, row_height: f32, items: &[String], selected: usize) -> ListResponse {
+        let mut response = ListResponse { hovered: None, clicked: None };
+        for (i, item) in items.iter().enumerate() {
+            let rect = Rect::new(x, y + i as f32 * row_height, width, row_height);
+            let is_selected = i == selected;
+            if self.hovered(rect) {
+                response.hovered = Some(i);
+                if is_mouse_button_pressed(MouseButton::Left) {
+                    response.clicked = Some(i);
+                }
+            }
+            if is_selected || response.hovered == Some(i) {
+                draw_rectangle(rect.x, rect.y, rect.w, rect.h, HOVER_COLOR);
+            }
+            let text_size = if is_selected { row_height * 0.7 } else { row_height * 0.6 };
+            let color = if is_selected { WHITE } else { GRAY };
+            draw_text(item, rect.x + 12.0, rect.y + rect.h * 0.7, text_size, color);
+        }
+        response
+    }
+
+    // A horizontal slider; dragging anywhere on it sets the value
+    pub fn slider(&mut self, rect: Rect, value: f32, min: f32, max: f32) -> f32 {
+        let track_y = rect.y + rect.h / 2.0;
+        draw_line(rect.x, track_y, rect.x + rect.w, track_y, 2.0, DARKGRAY);
+        let mut value = value.clamp(min, max);
+        if self.hovered(rect) && is_mouse_button_down(MouseButton::Left) && rect.w > 0.0 {
+            let t = ((self.mouse().x - rect.x) / rect.w).clamp(0.0, 1.0);
+            value = min + t * (max - min);
+        }
+        let t = if max > min { (value - min) / (max - min) } else { 0.0 };
+        let knob_color = if self.hovered(rect) { WHITE } else { LIGHTGRAY };
+        draw_circle(rect.x + t * rect.w, track_y, rect.h / 3.0, knob_color);
+        value
+    }
+
+    // Shows text next to the mouse once it has rested on the rect for a moment
+    pub fn tooltip(&mut self, rect: Rect, text: &str) {
+        if self.hovered(rect) && self.hover_time >= TOOLTIP_DELAY {
+            self.tooltip = Some(text.to_string());
+        }
+    }
+
+    // Call once at the end of every frame, after everything else is drawn
+    pub fn draw_tooltip(&self) {
+        let Some(text) = &self.tooltip else { return };
+        let dims = measure_text(text, None, 16, 1.0);
+        let (mouse_x, mouse_y) = mouse_position();
+        let x = (mouse_x + 14.0).min(screen_width() - dims.width - 12.0);
+        let y = mouse_y + 20.0;
+        draw_rectangle(x - 6.0, y - 16.0, dims.width + 12.0, 24.0, Color::new(0.05, 0.05, 0.1, 0.95));
+        draw_rectangle_lines(x - 6.0, y - 16.0, dims.width + 12.0, 24.0, 1.0, SKYBLUE);
+        draw_text(text, x, y, 16.0, WHITE);
+    }
+}